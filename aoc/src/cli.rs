@@ -0,0 +1,40 @@
+//! Command line flags that every day's binary understands the same way.
+
+use crate::parse::{number, ParseError};
+
+/// The `SIZE SEED` following `flag`, as in `--generate 1000 7`, or `None` when the flag isn't
+/// there at all. Fails when either is missing or isn't a number.
+pub fn size_and_seed(args: &[String], flag: &str) -> Result<Option<(usize, u64)>, ParseError> {
+    let Some(at) = args.iter().position(|arg| arg == flag) else {
+        return Ok(None);
+    };
+    match &args[at + 1..] {
+        [size, seed, ..] => Ok(Some((number(size)?, number(seed)?))),
+        _ => Err(ParseError::new(&args[at], "a size and a seed after it")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::size_and_seed;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn reads_the_numbers_after_the_flag() {
+        let args = args("day1 --allocs --generate 1000 7");
+        assert_eq!(size_and_seed(&args, "--generate"), Ok(Some((1000, 7))));
+        assert_eq!(size_and_seed(&args, "--bench"), Ok(None));
+    }
+
+    #[test]
+    fn complains_instead_of_guessing() {
+        let err = size_and_seed(&args("day1 --generate 1000"), "--generate").unwrap_err();
+        assert_eq!(err.found, "--generate");
+        let err = size_and_seed(&args("day1 --generate lots 7"), "--generate").unwrap_err();
+        assert_eq!(err.found, "lots");
+        assert!(size_and_seed(&args("day1 --generate 1000 -7"), "--generate").is_err());
+    }
+}
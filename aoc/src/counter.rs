@@ -0,0 +1,244 @@
+//! A multiset: how many times each thing turns up.
+
+use std::{
+    collections::{hash_map, HashMap},
+    hash::Hash,
+    ops::{Add, BitAnd, BitOr, Sub},
+};
+
+/// Counts of hashable things. Nothing is ever stored with a count of zero, so two counters
+/// holding the same counts compare equal however they got there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter<T: Hash + Eq> {
+    counts: HashMap<T, usize>,
+}
+
+impl<T: Hash + Eq> Counter<T> {
+    pub fn new() -> Self {
+        Counter {
+            counts: HashMap::new(),
+        }
+    }
+
+    /// Counts one more `item`.
+    pub fn add(&mut self, item: T) {
+        self.add_n(item, 1);
+    }
+
+    /// Counts `n` more of `item`.
+    pub fn add_n(&mut self, item: T, n: usize) {
+        if n > 0 {
+            *self.counts.entry(item).or_insert(0) += n;
+        }
+    }
+
+    /// Counts `n` more of `item`, returning the new count, or `None` without changing anything
+    /// if it won't fit in a `usize`.
+    pub fn checked_add_n(&mut self, item: T, n: usize) -> Option<usize> {
+        let count = self.get(&item).checked_add(n)?;
+        if count > 0 {
+            self.counts.insert(item, count);
+        }
+        Some(count)
+    }
+
+    /// How many of `item` there are, zero if it was never counted.
+    pub fn get(&self, item: &T) -> usize {
+        self.counts.get(item).copied().unwrap_or(0)
+    }
+
+    pub fn contains(&self, item: &T) -> bool {
+        self.counts.contains_key(item)
+    }
+
+    /// Forgets `item` entirely, returning how many of it there were.
+    pub fn remove(&mut self, item: &T) -> usize {
+        self.counts.remove(item).unwrap_or(0)
+    }
+
+    /// How many distinct things have been counted.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// The sum of all the counts.
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// The sum of all the counts, or `None` if it won't fit in a `usize`.
+    pub fn checked_total(&self) -> Option<usize> {
+        self.counts
+            .values()
+            .try_fold(0_usize, |total, n| total.checked_add(*n))
+    }
+
+    /// Each distinct thing with its count, in no particular order.
+    pub fn iter(&self) -> hash_map::Iter<'_, T, usize> {
+        self.counts.iter()
+    }
+
+    /// Just the counts, biggest first. Two hands of cards have the same signature exactly
+    /// when they're the same shape, whatever the cards are.
+    pub fn signature(&self) -> Vec<usize> {
+        let mut counts: Vec<usize> = self.counts.values().copied().collect();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+        counts
+    }
+}
+
+impl<T: Hash + Eq + Ord> Counter<T> {
+    /// Every distinct thing with its count, most common first and smallest first on a tie.
+    pub fn most_common(&self) -> Vec<(&T, usize)> {
+        let mut common: Vec<(&T, usize)> = self.counts.iter().map(|(t, n)| (t, *n)).collect();
+        common.sort_unstable_by(|(a, m), (b, n)| n.cmp(m).then(a.cmp(b)));
+        common
+    }
+}
+
+impl<T: Hash + Eq> Default for Counter<T> {
+    fn default() -> Self {
+        Counter::new()
+    }
+}
+
+impl<T: Hash + Eq> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter = Counter::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<T: Hash + Eq> Extend<T> for Counter<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.add(item);
+        }
+    }
+}
+
+impl<'a, T: Hash + Eq> IntoIterator for &'a Counter<T> {
+    type Item = (&'a T, &'a usize);
+    type IntoIter = hash_map::Iter<'a, T, usize>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Both counts added together.
+impl<T: Hash + Eq + Clone> Add for &Counter<T> {
+    type Output = Counter<T>;
+
+    fn add(self, other: &Counter<T>) -> Counter<T> {
+        let mut sum = self.clone();
+        for (item, n) in other {
+            sum.add_n(item.clone(), *n);
+        }
+        sum
+    }
+}
+
+/// The left counts with the right ones taken away, dropping anything that runs out.
+impl<T: Hash + Eq + Clone> Sub for &Counter<T> {
+    type Output = Counter<T>;
+
+    fn sub(self, other: &Counter<T>) -> Counter<T> {
+        self.iter()
+            .filter(|(item, n)| **n > other.get(item))
+            .map(|(item, n)| (item.clone(), n - other.get(item)))
+            .collect::<HashMap<_, _>>()
+            .into()
+    }
+}
+
+/// The smaller of the two counts: what both have in common.
+impl<T: Hash + Eq + Clone> BitAnd for &Counter<T> {
+    type Output = Counter<T>;
+
+    fn bitand(self, other: &Counter<T>) -> Counter<T> {
+        self.iter()
+            .filter(|(item, _)| other.contains(item))
+            .map(|(item, n)| (item.clone(), *n.min(&other.get(item))))
+            .collect::<HashMap<_, _>>()
+            .into()
+    }
+}
+
+/// The larger of the two counts.
+impl<T: Hash + Eq + Clone> BitOr for &Counter<T> {
+    type Output = Counter<T>;
+
+    fn bitor(self, other: &Counter<T>) -> Counter<T> {
+        let mut union = self.clone();
+        for (item, n) in other {
+            let count = union.counts.entry(item.clone()).or_insert(0);
+            *count = (*count).max(*n);
+        }
+        union
+    }
+}
+
+impl<T: Hash + Eq> From<HashMap<T, usize>> for Counter<T> {
+    fn from(mut counts: HashMap<T, usize>) -> Self {
+        counts.retain(|_, n| *n > 0);
+        Counter { counts }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Counter;
+
+    #[test]
+    fn counts_and_ranks() {
+        let letters: Counter<char> = "abracadabra".chars().collect();
+        assert_eq!(letters.get(&'a'), 5);
+        assert_eq!(letters.get(&'z'), 0);
+        assert_eq!(letters.len(), 5);
+        assert_eq!(letters.total(), 11);
+        assert_eq!(letters.signature(), vec![5, 2, 2, 1, 1]);
+        assert_eq!(
+            letters.most_common(),
+            vec![(&'a', 5), (&'b', 2), (&'r', 2), (&'c', 1), (&'d', 1)]
+        );
+    }
+
+    #[test]
+    fn never_keeps_zero_counts() {
+        let mut counter: Counter<u8> = [1, 1, 2].into_iter().collect();
+        counter.add_n(3, 0);
+        assert_eq!(counter.checked_add_n(3, 0), Some(0));
+        assert!(!counter.contains(&3));
+        assert_eq!(counter.remove(&2), 1);
+        assert_eq!(counter, [1, 1].into_iter().collect());
+    }
+
+    #[test]
+    fn refuses_to_overflow() {
+        let mut counter = Counter::new();
+        assert_eq!(counter.checked_add_n('a', usize::MAX), Some(usize::MAX));
+        assert_eq!(counter.checked_add_n('a', 1), None);
+        assert_eq!(counter.get(&'a'), usize::MAX);
+        assert_eq!(counter.checked_total(), Some(usize::MAX));
+        counter.add('b');
+        assert_eq!(counter.checked_total(), None);
+    }
+
+    #[test]
+    fn does_multiset_arithmetic() {
+        let a: Counter<char> = "aaabc".chars().collect();
+        let b: Counter<char> = "abbd".chars().collect();
+        assert_eq!(&a + &b, "aaaabbbcd".chars().collect());
+        assert_eq!(&a - &b, "aac".chars().collect());
+        assert_eq!(&b - &a, "bd".chars().collect());
+        assert_eq!(&a & &b, "ab".chars().collect());
+        assert_eq!(&a | &b, "aaabbcd".chars().collect());
+        assert!((&a - &a).is_empty());
+    }
+}
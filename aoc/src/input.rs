@@ -0,0 +1,62 @@
+//! Puzzle input cleanup, so the days only ever see one shape of text.
+
+use std::borrow::Cow;
+
+/// Irons out the differences between copies of the same input: drops a byte order mark,
+/// turns `\r\n` and lone `\r` into `\n`, turns tabs into spaces, strips whitespace from the
+/// ends of lines and drops blank lines at the very end, final newline included.
+///
+/// Input that's already clean comes back borrowed.
+pub fn normalize(raw: &str) -> Cow<'_, str> {
+    let text = raw.strip_prefix('\u{feff}').unwrap_or(raw);
+    let clean = !text.contains(['\r', '\t'])
+        && !text.ends_with(char::is_whitespace)
+        && !text.lines().any(|line| line.ends_with(char::is_whitespace));
+    if clean {
+        return Cow::Borrowed(text);
+    }
+
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+    let mut normalized = String::with_capacity(text.len());
+    for line in text.lines() {
+        normalized.push_str(
+            line.replace('\t', " ")
+                .trim_end_matches(char::is_whitespace),
+        );
+        normalized.push('\n');
+    }
+    normalized.truncate(normalized.trim_end().len());
+    Cow::Owned(normalized)
+}
+
+/// `text` with Windows line endings and a byte order mark, the way an input saved on another
+/// machine might turn up. Handy for checking a day doesn't care.
+pub fn as_windows(text: &str) -> String {
+    format!("\u{feff}{}\r\n", text.replace('\n', "\r\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::{as_windows, normalize};
+
+    #[test]
+    fn leaves_clean_input_alone() {
+        let input = "a b\n\nc";
+        assert!(matches!(normalize(input), Cow::Borrowed("a b\n\nc")));
+    }
+
+    #[test]
+    fn cleans_up_other_platforms() {
+        assert_eq!(normalize(&as_windows("a b\n\nc")), "a b\n\nc");
+        assert_eq!(normalize("a\rb\r\n"), "a\nb");
+        assert_eq!(
+            normalize("Time:\t7  15 \nDistance:\t9\n\n\n"),
+            "Time: 7  15\nDistance: 9"
+        );
+        assert_eq!(normalize("\u{feff}x"), "x");
+        assert_eq!(normalize(" \n\n"), "");
+        assert_eq!(normalize("a\u{a0}\nb\u{3000}\nc"), "a\nb\nc");
+    }
+}
@@ -0,0 +1,461 @@
+//! Half-open intervals, sets of them, and maps that shift pieces of the number line around.
+
+use std::fmt;
+
+/// The half-open interval `[start, end)`. An interval with `end <= start` is empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Interval {
+    pub fn new(start: i64, end: i64) -> Self {
+        Interval { start, end }
+    }
+
+    pub fn with_len(start: i64, len: i64) -> Self {
+        Interval::new(start, start + len)
+    }
+
+    pub fn len(&self) -> i64 {
+        (self.end - self.start).max(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    pub fn contains(&self, point: i64) -> bool {
+        self.start <= point && point < self.end
+    }
+
+    pub fn intersect(&self, other: &Interval) -> Option<Interval> {
+        let overlap = Interval::new(self.start.max(other.start), self.end.min(other.end));
+        if overlap.is_empty() {
+            return None;
+        }
+        Some(overlap)
+    }
+
+    pub fn shift(&self, offset: i64) -> Interval {
+        Interval::new(self.start + offset, self.end + offset)
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}, {})", self.start, self.end)
+    }
+}
+
+/// A set of points kept as sorted, disjoint, non-touching intervals.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        IntervalSet::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Number of points covered by the set.
+    pub fn len(&self) -> i64 {
+        self.intervals.iter().map(|i| i.len()).sum()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Interval> {
+        self.intervals.iter()
+    }
+
+    pub fn min(&self) -> Option<i64> {
+        self.intervals.first().map(|i| i.start)
+    }
+
+    pub fn shift(&self, offset: i64) -> IntervalSet {
+        IntervalSet {
+            intervals: self.iter().map(|i| i.shift(offset)).collect(),
+        }
+    }
+
+    pub fn contains(&self, point: i64) -> bool {
+        let idx = self.intervals.partition_point(|i| i.end <= point);
+        self.intervals.get(idx).is_some_and(|i| i.contains(point))
+    }
+
+    pub fn insert(&mut self, interval: Interval) {
+        self.intervals.push(interval);
+        self.normalize();
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        self.iter().chain(other.iter()).copied().collect()
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut out = Vec::new();
+        let (mut a, mut b) = (0, 0);
+        while a < self.intervals.len() && b < other.intervals.len() {
+            let (x, y) = (self.intervals[a], other.intervals[b]);
+            if let Some(overlap) = x.intersect(&y) {
+                out.push(overlap);
+            }
+            if x.end < y.end {
+                a += 1;
+            } else {
+                b += 1;
+            }
+        }
+        IntervalSet { intervals: out }
+    }
+
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut out = Vec::new();
+        let mut b = 0;
+        for interval in &self.intervals {
+            let mut current = interval.start;
+            while b < other.intervals.len() && other.intervals[b].end <= current {
+                b += 1;
+            }
+            let mut k = b;
+            while k < other.intervals.len() && other.intervals[k].start < interval.end {
+                let cut = other.intervals[k];
+                if cut.start > current {
+                    out.push(Interval::new(current, cut.start));
+                }
+                current = current.max(cut.end);
+                k += 1;
+            }
+            if current < interval.end {
+                out.push(Interval::new(current, interval.end));
+            }
+        }
+        IntervalSet { intervals: out }
+    }
+
+    fn normalize(&mut self) {
+        self.intervals.retain(|i| !i.is_empty());
+        self.intervals.sort_unstable();
+
+        let mut merged: Vec<Interval> = Vec::with_capacity(self.intervals.len());
+        for interval in self.intervals.drain(..) {
+            match merged.last_mut() {
+                Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+                _ => merged.push(interval),
+            }
+        }
+        self.intervals = merged;
+    }
+}
+
+impl fmt::Display for IntervalSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self.iter().map(|i| i.to_string()).collect();
+        write!(f, "{{{}}}", parts.join(", "))
+    }
+}
+
+impl From<Interval> for IntervalSet {
+    fn from(interval: Interval) -> Self {
+        std::iter::once(interval).collect()
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> Self {
+        let mut set = IntervalSet {
+            intervals: iter.into_iter().collect(),
+        };
+        set.normalize();
+        set
+    }
+}
+
+impl Extend<Interval> for IntervalSet {
+    fn extend<I: IntoIterator<Item = Interval>>(&mut self, iter: I) {
+        self.intervals.extend(iter);
+        self.normalize();
+    }
+}
+
+/// One piece of a [`PiecewiseMap`]: every point in `domain` gets `offset` added to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Piece {
+    pub domain: Interval,
+    pub offset: i64,
+}
+
+/// A map on the integers that shifts a handful of disjoint intervals and leaves every other
+/// point where it is.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PiecewiseMap {
+    pieces: Vec<Piece>,
+}
+
+impl PiecewiseMap {
+    pub fn new() -> Self {
+        PiecewiseMap::default()
+    }
+
+    /// Adds a piece. Points already covered by an earlier piece keep their earlier offset.
+    pub fn insert(&mut self, domain: Interval, offset: i64) {
+        let uncovered = IntervalSet::from(domain).difference(&self.domain());
+        self.pieces
+            .extend(uncovered.iter().map(|&domain| Piece { domain, offset }));
+        self.pieces.sort_unstable_by_key(|p| p.domain.start);
+    }
+
+    pub fn pieces(&self) -> impl Iterator<Item = &Piece> {
+        self.pieces.iter()
+    }
+
+    /// The points that are moved by an explicit piece, identity or not.
+    pub fn domain(&self) -> IntervalSet {
+        self.pieces.iter().map(|p| p.domain).collect()
+    }
+
+    pub fn apply(&self, point: i64) -> i64 {
+        let idx = self.pieces.partition_point(|p| p.domain.end <= point);
+        match self.pieces.get(idx) {
+            Some(piece) if piece.domain.contains(point) => point + piece.offset,
+            _ => point,
+        }
+    }
+
+    /// Cuts `interval` at the piece boundaries, pairing each chunk with the offset it gets.
+    /// Chunks outside every piece come back with an offset of zero.
+    fn split(&self, interval: &Interval) -> Vec<(Interval, i64)> {
+        let mut out = Vec::new();
+        let mut current = interval.start;
+        let first = self.pieces.partition_point(|p| p.domain.end <= current);
+
+        for piece in &self.pieces[first..] {
+            if piece.domain.start >= interval.end {
+                break;
+            }
+            if piece.domain.start > current {
+                out.push((Interval::new(current, piece.domain.start), 0));
+            }
+            if let Some(overlap) = piece.domain.intersect(interval) {
+                out.push((overlap, piece.offset));
+                current = overlap.end;
+            }
+        }
+        if current < interval.end {
+            out.push((Interval::new(current, interval.end), 0));
+        }
+
+        out
+    }
+
+    pub fn apply_to_interval(&self, interval: &Interval) -> IntervalSet {
+        self.split(interval)
+            .into_iter()
+            .map(|(chunk, offset)| chunk.shift(offset))
+            .collect()
+    }
+
+    pub fn apply_to_set(&self, set: &IntervalSet) -> IntervalSet {
+        set.iter()
+            .flat_map(|interval| self.apply_to_interval(interval).intervals)
+            .collect()
+    }
+
+    /// Every point that lands somewhere in `target`.
+    pub fn preimage(&self, target: &IntervalSet) -> IntervalSet {
+        let mut out = target.difference(&self.domain());
+        for piece in &self.pieces {
+            let landed = target.shift(-piece.offset);
+            out.extend(landed.intersection(&piece.domain.into()).intervals);
+        }
+        out
+    }
+
+    /// The map that applies `self` and then `next`.
+    pub fn then(&self, next: &PiecewiseMap) -> PiecewiseMap {
+        let cover = self.domain().union(&next.domain());
+        let mut pieces: Vec<Piece> = Vec::new();
+
+        for interval in cover.iter() {
+            for (chunk, first) in self.split(interval) {
+                for (landed, second) in next.split(&chunk.shift(first)) {
+                    let domain = landed.shift(-first);
+                    let offset = first + second;
+                    if offset == 0 {
+                        continue;
+                    }
+                    match pieces.last_mut() {
+                        Some(last) if last.domain.end == domain.start && last.offset == offset => {
+                            last.domain.end = domain.end
+                        }
+                        _ => pieces.push(Piece { domain, offset }),
+                    }
+                }
+            }
+        }
+
+        PiecewiseMap { pieces }
+    }
+
+    /// The inverse map, if the map is a bijection. Fails when two pieces land on top of each
+    /// other or a piece lands on a point that is left alone.
+    pub fn inverse(&self) -> Option<PiecewiseMap> {
+        let images: Vec<Interval> = self
+            .pieces
+            .iter()
+            .map(|p| p.domain.shift(p.offset))
+            .collect();
+        let image_set: IntervalSet = images.iter().copied().collect();
+        if image_set.len() != self.domain().len() || image_set != self.domain() {
+            return None;
+        }
+
+        let mut pieces: Vec<Piece> = images
+            .into_iter()
+            .zip(&self.pieces)
+            .map(|(domain, p)| Piece {
+                domain,
+                offset: -p.offset,
+            })
+            .collect();
+        pieces.sort_unstable_by_key(|p| p.domain.start);
+        Some(PiecewiseMap { pieces })
+    }
+}
+
+impl fmt::Display for PiecewiseMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for piece in &self.pieces {
+            writeln!(
+                f,
+                "{} -> {} ({:+})",
+                piece.domain,
+                piece.domain.shift(piece.offset),
+                piece.offset
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Interval, IntervalSet, PiecewiseMap};
+
+    fn set(intervals: &[(i64, i64)]) -> IntervalSet {
+        intervals
+            .iter()
+            .map(|&(s, e)| Interval::new(s, e))
+            .collect()
+    }
+
+    #[test]
+    fn normalizes_overlapping_and_touching_intervals() {
+        let normalized = set(&[(5, 8), (0, 2), (2, 3), (7, 10), (12, 12)]);
+        assert_eq!(normalized, set(&[(0, 3), (5, 10)]));
+        assert_eq!(normalized.len(), 8);
+        assert!(normalized.contains(9));
+        assert!(!normalized.contains(10));
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25)]);
+        assert_eq!(a.union(&b), set(&[(0, 30)]));
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 25)]));
+        assert_eq!(a.difference(&b), set(&[(0, 5), (25, 30)]));
+        assert_eq!(b.difference(&a), set(&[(10, 20)]));
+        assert_eq!(a.difference(&a), IntervalSet::new());
+    }
+
+    #[test]
+    fn set_operations_agree_with_points() {
+        let a = set(&[(0, 3), (6, 9), (11, 14)]);
+        let b = set(&[(2, 7), (8, 12), (13, 20)]);
+        for p in -1..21 {
+            assert_eq!(a.union(&b).contains(p), a.contains(p) || b.contains(p));
+            assert_eq!(
+                a.intersection(&b).contains(p),
+                a.contains(p) && b.contains(p)
+            );
+            assert_eq!(
+                a.difference(&b).contains(p),
+                a.contains(p) && !b.contains(p)
+            );
+        }
+    }
+
+    #[test]
+    fn earlier_pieces_win() {
+        let mut map = PiecewiseMap::new();
+        map.insert(Interval::new(10, 20), 100);
+        map.insert(Interval::new(15, 25), -5);
+        assert_eq!(map.apply(9), 9);
+        assert_eq!(map.apply(15), 115);
+        assert_eq!(map.apply(20), 15);
+        assert_eq!(map.apply(25), 25);
+    }
+
+    #[test]
+    fn applies_to_a_set_like_it_applies_to_points() {
+        let mut map = PiecewiseMap::new();
+        map.insert(Interval::new(98, 100), -48);
+        map.insert(Interval::new(50, 98), 2);
+
+        let seeds = set(&[(79, 93), (55, 68), (0, 3), (97, 105)]);
+        let expected: IntervalSet = seeds
+            .iter()
+            .flat_map(|i| i.start..i.end)
+            .map(|p| Interval::new(map.apply(p), map.apply(p) + 1))
+            .collect();
+        assert_eq!(map.apply_to_set(&seeds), expected);
+    }
+
+    fn map(pieces: &[(i64, i64, i64)]) -> PiecewiseMap {
+        let mut map = PiecewiseMap::new();
+        for &(start, end, offset) in pieces {
+            map.insert(Interval::new(start, end), offset);
+        }
+        map
+    }
+
+    #[test]
+    fn composes_maps() {
+        let first = map(&[(0, 10, 5), (10, 15, -10)]);
+        let second = map(&[(3, 8, 100), (12, 20, -12)]);
+        let composed = first.then(&second);
+        for p in -5..30 {
+            assert_eq!(composed.apply(p), second.apply(first.apply(p)), "at {p}");
+        }
+    }
+
+    #[test]
+    fn finds_preimages() {
+        let folded = map(&[(0, 10, 10), (10, 20, -10)]);
+        assert_eq!(folded.preimage(&set(&[(5, 15)])), set(&[(0, 5), (15, 20)]));
+
+        let squashed = map(&[(0, 10, 10)]);
+        assert_eq!(
+            squashed.preimage(&set(&[(12, 13)])),
+            set(&[(2, 3), (12, 13)])
+        );
+    }
+
+    #[test]
+    fn inverts_bijections_only() {
+        let swap = map(&[(0, 10, 10), (10, 20, -10)]);
+        let inverse = swap.inverse().unwrap();
+        for p in -5..25 {
+            assert_eq!(inverse.apply(swap.apply(p)), p);
+        }
+
+        assert_eq!(map(&[(0, 10, 10)]).inverse(), None);
+        assert_eq!(map(&[(0, 10, 5), (10, 20, -5)]).inverse(), None);
+    }
+}
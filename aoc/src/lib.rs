@@ -0,0 +1,10 @@
+//! Bits and pieces that keep showing up across the days.
+
+pub mod alloc;
+pub mod cli;
+pub mod counter;
+pub mod input;
+pub mod interval;
+pub mod math;
+pub mod parse;
+pub mod rng;
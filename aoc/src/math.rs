@@ -0,0 +1,450 @@
+//! Number theory the puzzles keep reaching for, written once over any integer type.
+//!
+//! Everything works for the primitive integers, and for `num_bigint::BigInt` with the
+//! `bigint` feature on. Functions that can overflow say so with an `Option` rather than
+//! wrapping or panicking.
+
+use std::{
+    fmt::Debug,
+    ops::{Add, Div, Mul, Neg, Rem, Sub},
+};
+
+/// What the functions in this module need from a number type.
+pub trait Integer:
+    Clone
+    + Ord
+    + Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    fn zero() -> Self;
+    fn one() -> Self;
+    /// `None` when the sum doesn't fit.
+    fn try_add(&self, other: &Self) -> Option<Self>;
+    /// `None` when the product doesn't fit.
+    fn try_mul(&self, other: &Self) -> Option<Self>;
+    /// `None` when `-self` doesn't fit, as with `MIN` of a signed type.
+    fn try_neg(&self) -> Option<Self>;
+    /// `None` when the remainder overflows, which is only `MIN % -1`.
+    fn try_rem(&self, other: &Self) -> Option<Self>;
+
+    fn is_zero(&self) -> bool {
+        *self == Self::zero()
+    }
+
+    fn is_negative(&self) -> bool {
+        *self < Self::zero()
+    }
+}
+
+macro_rules! primitive {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                fn zero() -> Self {
+                    0
+                }
+
+                fn one() -> Self {
+                    1
+                }
+
+                fn try_add(&self, other: &Self) -> Option<Self> {
+                    self.checked_add(*other)
+                }
+
+                fn try_mul(&self, other: &Self) -> Option<Self> {
+                    self.checked_mul(*other)
+                }
+
+                fn try_neg(&self) -> Option<Self> {
+                    self.checked_neg()
+                }
+
+                fn try_rem(&self, other: &Self) -> Option<Self> {
+                    self.checked_rem(*other)
+                }
+            }
+        )*
+    };
+}
+
+primitive!(u32, u64, u128, usize, i32, i64, i128);
+
+#[cfg(feature = "bigint")]
+impl Integer for num_bigint::BigInt {
+    fn zero() -> Self {
+        num_bigint::BigInt::from(0)
+    }
+
+    fn one() -> Self {
+        num_bigint::BigInt::from(1)
+    }
+
+    fn try_add(&self, other: &Self) -> Option<Self> {
+        Some(self + other)
+    }
+
+    fn try_mul(&self, other: &Self) -> Option<Self> {
+        Some(self * other)
+    }
+
+    fn try_neg(&self) -> Option<Self> {
+        Some(-self)
+    }
+
+    fn try_rem(&self, other: &Self) -> Option<Self> {
+        Some(self % other)
+    }
+}
+
+fn checked_abs<T: Integer>(n: T) -> Option<T> {
+    match n.is_negative() {
+        true => n.try_neg(),
+        false => Some(n),
+    }
+}
+
+/// `n` reduced into `[0, m)` for a positive `m`, whatever the sign of `n`.
+pub fn modulo<T: Integer>(n: T, m: &T) -> T {
+    let r = n % m.clone();
+    match r.is_negative() {
+        true => r + m.clone(),
+        false => r,
+    }
+}
+
+/// `(a + b) mod m` for `a` and `b` already in `[0, m)`, without ever going past `m`.
+fn add_mod<T: Integer>(a: T, b: T, m: &T) -> T {
+    let gap = m.clone() - b.clone();
+    match a >= gap {
+        true => a - gap,
+        false => a + b,
+    }
+}
+
+/// `(a - b) mod m` for `a` and `b` already in `[0, m)`.
+fn sub_mod<T: Integer>(a: T, b: T, m: &T) -> T {
+    match a >= b {
+        true => a - b,
+        false => a + (m.clone() - b),
+    }
+}
+
+/// `(a * b) mod m` for `a` and `b` in `[0, m)`. Falls back to doubling and adding when the
+/// product itself won't fit, so it works right up to the top of the type.
+pub fn mul_mod<T: Integer>(a: T, b: T, m: &T) -> T {
+    if let Some(product) = a.try_mul(&b) {
+        return product % m.clone();
+    }
+    let two = T::one() + T::one();
+    let (mut a, mut b) = (a, b);
+    let mut product = T::zero();
+    while !b.is_zero() {
+        if !(b.clone() % two.clone()).is_zero() {
+            product = add_mod(product, a.clone(), m);
+        }
+        a = add_mod(a.clone(), a, m);
+        b = b / two.clone();
+    }
+    product
+}
+
+/// The greatest common divisor, never negative. `gcd(0, 0)` is 0. Panics if it doesn't fit;
+/// see `checked_gcd`.
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    checked_gcd(a, b).expect("gcd should fit in the type")
+}
+
+/// The greatest common divisor, or `None` if it doesn't fit. That only happens when it's
+/// `-MIN`, as in `gcd(i64::MIN, 0)`.
+pub fn checked_gcd<T: Integer>(a: T, b: T) -> Option<T> {
+    let (mut a, mut b) = (a, b);
+    while !b.is_zero() {
+        // `MIN % -1` overflows, but the remainder it stands for is zero
+        let r = a.try_rem(&b).unwrap_or_else(T::zero);
+        a = b;
+        b = r;
+    }
+    checked_abs(a)
+}
+
+/// The least common multiple, never negative. Panics if it doesn't fit; see `checked_lcm`.
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    checked_lcm(a, b).expect("lcm should fit in the type")
+}
+
+/// The least common multiple, or `None` if it doesn't fit.
+pub fn checked_lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    if a.is_zero() || b.is_zero() {
+        return Some(T::zero());
+    }
+    let g = checked_gcd(a.clone(), b.clone())?;
+    checked_abs(a / g)?.try_mul(&checked_abs(b)?)
+}
+
+/// `(g, x, y)` with `g = gcd(a, b)` and `a * x + b * y = g`.
+pub fn extended_gcd<T: Integer + Neg<Output = T>>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_s, mut s) = (T::one(), T::zero());
+    let (mut old_t, mut t) = (T::zero(), T::one());
+    while !r.is_zero() {
+        let q = old_r.clone() / r.clone();
+        (old_r, r) = (r.clone(), old_r - q.clone() * r);
+        (old_s, s) = (s.clone(), old_s - q.clone() * s);
+        (old_t, t) = (t.clone(), old_t - q * t);
+    }
+    match old_r.is_negative() {
+        true => (-old_r, -old_s, -old_t),
+        false => (old_r, old_s, old_t),
+    }
+}
+
+/// The `x` in `[0, m)` with `a * x = 1 (mod m)`, if there is one. `m` must be positive.
+///
+/// This is extended Euclid with the coefficients kept reduced mod `m`, so it needs no
+/// negative numbers and works for unsigned types too.
+pub fn mod_inverse<T: Integer>(a: T, m: T) -> Option<T> {
+    assert!(
+        !m.is_negative() && !m.is_zero(),
+        "modulus should be positive"
+    );
+    let (mut old_r, mut r) = (modulo(a, &m), m.clone());
+    let (mut old_s, mut s) = (T::one() % m.clone(), T::zero());
+    while !r.is_zero() {
+        let q = old_r.clone() / r.clone();
+        (old_r, r) = (r.clone(), old_r - q.clone() * r);
+        let step = mul_mod(q % m.clone(), s.clone(), &m);
+        (old_s, s) = (s.clone(), sub_mod(old_s, step, &m));
+    }
+    match old_r == T::one() {
+        true => Some(old_s),
+        false => None,
+    }
+}
+
+/// Merges `x = a (mod m)` and `x = b (mod n)` into one congruence `x = c (mod lcm(m, n))`.
+/// The moduli needn't be coprime. `None` when the two disagree, or when the merged modulus
+/// doesn't fit in the type.
+pub fn crt<T: Integer>((a, m): (T, T), (b, n): (T, T)) -> Option<(T, T)> {
+    let (a, b) = (modulo(a, &m), modulo(b, &n));
+    let g = gcd(m.clone(), n.clone());
+    let diff = sub_mod(b, a.clone() % n.clone(), &n);
+    if !(diff.clone() % g.clone()).is_zero() {
+        return None;
+    }
+    let (m_g, n_g) = (m.clone() / g.clone(), n.clone() / g.clone());
+    // Solve m/g * k = diff/g (mod n/g) for k
+    let k = mul_mod(
+        diff / g % n_g.clone(),
+        mod_inverse(m_g.clone() % n_g.clone(), n_g.clone())?,
+        &n_g,
+    );
+    let modulus = m_g.try_mul(&n)?;
+    // k < n/g, so m * k stays under the merged modulus
+    Some((add_mod(a, m * k, &modulus), modulus))
+}
+
+/// Folds any number of congruences together with `crt`.
+pub fn crt_all<T: Integer>(congruences: impl IntoIterator<Item = (T, T)>) -> Option<(T, T)> {
+    congruences
+        .into_iter()
+        .try_fold((T::zero(), T::one()), |acc, next| crt(acc, next))
+}
+
+/// The largest `r` with `r * r <= n`. Panics on a negative `n`.
+pub fn isqrt<T: Integer>(n: T) -> T {
+    assert!(!n.is_negative(), "square root of a negative number");
+    let two = T::one() + T::one();
+    if n < two {
+        return n;
+    }
+    // Newton's method from above. Starting at n/2 + 1 keeps x + n/x from overflowing.
+    let mut root = n.clone() / two.clone() + T::one();
+    loop {
+        let next = (root.clone() + n.clone() / root.clone()) / two.clone();
+        if next >= root {
+            return root;
+        }
+        root = next;
+    }
+}
+
+/// `base` to the power `exp`, or `None` if it doesn't fit.
+pub fn checked_pow<T: Integer>(base: T, exp: u32) -> Option<T> {
+    let (mut base, mut exp) = (base, exp);
+    let mut result = T::one();
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result.try_mul(&base)?;
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = base.try_mul(&base)?;
+        }
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::{
+        checked_gcd, checked_lcm, checked_pow, crt, crt_all, extended_gcd, gcd, isqrt, lcm,
+        mod_inverse, modulo, mul_mod,
+    };
+
+    #[test]
+    fn gcd_and_lcm_ignore_signs() {
+        assert_eq!(gcd(12_u64, 18), 6);
+        assert_eq!(gcd(-12_i64, 18), 6);
+        assert_eq!(gcd(0_i128, -7), 7);
+        assert_eq!(gcd(0_u32, 0), 0);
+        assert_eq!(lcm(-4_i64, 6), 12);
+        assert_eq!(lcm(0_u64, 6), 0);
+        assert_eq!(checked_lcm(u64::MAX, u64::MAX - 1), None);
+    }
+
+    #[test]
+    fn min_only_overflows_when_the_answer_does() {
+        assert_eq!(checked_gcd(i64::MIN, 0), None);
+        assert_eq!(checked_gcd(i64::MIN, i64::MIN), None);
+        assert_eq!(checked_gcd(i64::MIN, -1), Some(1));
+        assert_eq!(checked_gcd(6, i64::MIN), Some(2));
+        assert_eq!(checked_lcm(i64::MIN, 1), None);
+        assert_eq!(checked_lcm(i64::MIN, -1), None);
+        assert_eq!(checked_lcm(i64::MIN / 2, 2), Some(-(i64::MIN / 2)));
+    }
+
+    #[test]
+    fn extended_gcd_finds_bezout_coefficients() {
+        let (g, x, y) = extended_gcd(240_i64, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+        let (g, x, y) = extended_gcd(-240_i64, 46);
+        assert_eq!(g, 2);
+        assert_eq!(-240 * x + 46 * y, 2);
+    }
+
+    #[test]
+    fn inverts_where_it_can() {
+        assert_eq!(mod_inverse(3_u64, 11), Some(4));
+        assert_eq!(mod_inverse(-3_i64, 11), Some(7));
+        assert_eq!(mod_inverse(6_u64, 9), None);
+        assert_eq!(mod_inverse(5_u64, 1), Some(0));
+        // Big enough that the coefficients can't be multiplied out directly
+        let p = u128::MAX - 158; // the largest 128-bit prime
+        let inverse = mod_inverse(2, p).unwrap();
+        assert_eq!(mul_mod(2, inverse, &p), 1);
+    }
+
+    #[test]
+    fn merges_congruences() {
+        assert_eq!(crt((2_u64, 3), (3, 5)), Some((8, 15)));
+        assert_eq!(crt((2_u64, 4), (4, 6)), Some((10, 12)));
+        assert_eq!(crt((1_u64, 4), (2, 6)), None);
+        assert_eq!(crt((1_u128, 4), (3, 6)), Some((9, 12)));
+        assert_eq!(crt((0_u128, 4), (1, 6)), None);
+        assert_eq!(crt((-1_i64, 4), (0, 3)), Some((3, 12)));
+        assert_eq!(crt_all([(2_u64, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt((0_u64, u64::MAX), (0, u64::MAX - 1)), None);
+    }
+
+    #[test]
+    fn square_roots_reach_the_top_of_the_type() {
+        assert_eq!(isqrt(0_u64), 0);
+        assert_eq!(isqrt(1_u64), 1);
+        assert_eq!(isqrt(15_u64), 3);
+        assert_eq!(isqrt(16_u64), 4);
+        assert_eq!(isqrt(u64::MAX), u32::MAX as u64);
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+        assert_eq!(isqrt(i128::MAX), 13_043_817_825_332_782_212);
+    }
+
+    #[test]
+    fn powers_stop_at_overflow() {
+        assert_eq!(checked_pow(2_usize, 10), Some(1024));
+        assert_eq!(checked_pow(7_u64, 0), Some(1));
+        assert_eq!(checked_pow(-3_i64, 3), Some(-27));
+        assert_eq!(checked_pow(2_u64, 63), Some(1 << 63));
+        assert_eq!(checked_pow(2_u64, 64), None);
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn works_on_big_integers() {
+        use num_bigint::BigInt;
+
+        let big = checked_pow(BigInt::from(2), 200).unwrap();
+        assert_eq!(
+            isqrt(big.clone()),
+            checked_pow(BigInt::from(2), 100).unwrap()
+        );
+        let (g, x, y) = extended_gcd(big.clone(), BigInt::from(3));
+        assert_eq!(g, BigInt::from(1));
+        assert_eq!(big * x + BigInt::from(3) * y, BigInt::from(1));
+    }
+
+    proptest! {
+        #[test]
+        fn isqrt_brackets_the_root(n: u64) {
+            let root = isqrt(n) as u128;
+            prop_assert!(root * root <= n as u128);
+            prop_assert!((root + 1) * (root + 1) > n as u128);
+        }
+
+        #[test]
+        fn isqrt_brackets_wide_roots(n: u128) {
+            let root = isqrt(n);
+            prop_assert!(root * root <= n);
+            prop_assert!((root + 1).checked_mul(root + 1).is_none_or(|next| next > n));
+        }
+
+        #[test]
+        fn bezout_holds(a in -1_000_000_i64..1_000_000, b in -1_000_000_i64..1_000_000) {
+            let (g, x, y) = extended_gcd(a, b);
+            prop_assert_eq!(g, gcd(a, b));
+            prop_assert_eq!(a as i128 * x as i128 + b as i128 * y as i128, g as i128);
+        }
+
+        #[test]
+        fn inverses_multiply_to_one(a: u64, m in 2_u64..) {
+            match mod_inverse(a, m) {
+                Some(x) => prop_assert_eq!(a as u128 * x as u128 % m as u128, 1),
+                None => prop_assert_ne!(gcd(a, m), 1),
+            }
+        }
+
+        #[test]
+        fn crt_agrees_with_searching(a in 0_u64..60, m in 1_u64..60, b in 0_u64..60, n in 1_u64..60) {
+            let found = (0..m * n).find(|x| x % m == a % m && x % n == b % n);
+            let merged = crt((a, m), (b, n));
+            prop_assert_eq!(merged.map(|(x, _)| x), found);
+            if let Some((_, modulus)) = merged {
+                prop_assert_eq!(modulus, lcm(m, n));
+            }
+        }
+
+        #[test]
+        fn mul_mod_matches_wide_arithmetic(a: u64, b: u64, m in 1_u64..) {
+            let expected = (a as u128 % m as u128) * (b as u128 % m as u128) % m as u128;
+            prop_assert_eq!(mul_mod(a % m, b % m, &m) as u128, expected);
+        }
+
+        #[test]
+        fn modulo_is_never_negative(n: i64, m in 1_i64..) {
+            let r = modulo(n, &m);
+            prop_assert!((0..m).contains(&r));
+            prop_assert_eq!((n as i128 - r as i128) % m as i128, 0);
+        }
+
+        #[test]
+        fn checked_pow_matches_std(base: u32, exp in 0_u32..40) {
+            prop_assert_eq!(checked_pow(base as u64, exp), (base as u64).checked_pow(exp));
+        }
+    }
+}
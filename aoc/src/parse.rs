@@ -0,0 +1,253 @@
+//! Small parsers for the shapes puzzle inputs come in, with errors that say where they went
+//! wrong.
+//!
+//! Every parser hands back slices of the text it was given, never copies, so an error raised
+//! deep inside a line still knows where it is. Call [`ParseError::locate`] with the whole
+//! input to turn that into a line and column.
+
+use std::{fmt, str::FromStr};
+
+/// Something in the input that wasn't what a parser expected.
+#[derive(Debug, Clone, Eq)]
+pub struct ParseError {
+    pub expected: String,
+    pub found: String,
+    /// 1-based line and column of the offending text, once it's been located.
+    pub location: Option<(usize, usize)>,
+    /// Where the offending text sits in memory, which is all `locate` needs to find it again.
+    address: usize,
+}
+
+impl ParseError {
+    /// An error about `found`, which has to be a slice of the input for `locate` to work.
+    pub fn new(found: &str, expected: impl Into<String>) -> Self {
+        ParseError {
+            expected: expected.into(),
+            found: found.to_string(),
+            location: None,
+            address: found.as_ptr() as usize,
+        }
+    }
+
+    /// Works out the line and column of the error within `source`. Errors that didn't come
+    /// from somewhere inside `source` are left as they are.
+    pub fn locate(mut self, source: &str) -> Self {
+        let start = source.as_ptr() as usize;
+        let before = self
+            .address
+            .checked_sub(start)
+            .and_then(|offset| source.get(..offset));
+        if let Some(before) = before {
+            let line = before.matches('\n').count() + 1;
+            let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+            self.location = Some((line, column));
+        }
+        self
+    }
+}
+
+/// Errors are the same error wherever the text they're about happens to live.
+impl PartialEq for ParseError {
+    fn eq(&self, other: &Self) -> bool {
+        self.expected == other.expected
+            && self.found == other.found
+            && self.location == other.location
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some((line, column)) = self.location {
+            write!(f, "line {line}, column {column}: ")?;
+        }
+        write!(f, "expected {}, found {:?}", self.expected, self.found)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub type Result<T> = std::result::Result<T, ParseError>;
+
+fn digits(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// An unsigned number, ignoring whitespace around it.
+pub fn number<T: FromStr>(s: &str) -> Result<T> {
+    let s = s.trim();
+    if !digits(s) {
+        return Err(ParseError::new(s, "a number"));
+    }
+    s.parse()
+        .map_err(|_| ParseError::new(s, "a number small enough to hold"))
+}
+
+/// A number with an optional `+` or `-` in front, ignoring whitespace around it.
+pub fn signed<T: FromStr>(s: &str) -> Result<T> {
+    let s = s.trim();
+    let unsigned = s.strip_prefix(['+', '-']).unwrap_or(s);
+    if !digits(unsigned) {
+        return Err(ParseError::new(s, "a signed number"));
+    }
+    // `FromStr` for the integers takes a leading `+` but not a `+` and a `-` together
+    s.parse()
+        .map_err(|_| ParseError::new(s, "a number small enough to hold"))
+}
+
+/// Whitespace separated unsigned numbers.
+pub fn numbers<T: FromStr>(s: &str) -> Result<Vec<T>> {
+    s.split_whitespace().map(number).collect()
+}
+
+/// Items separated by `separator`, each trimmed and handed to `parse`.
+pub fn list<'a, T>(
+    s: &'a str,
+    separator: &str,
+    parse: impl FnMut(&'a str) -> Result<T>,
+) -> Result<Vec<T>> {
+    s.split(separator).map(str::trim).map(parse).collect()
+}
+
+/// The trimmed text either side of the first `separator`.
+pub fn split_pair<'a>(s: &'a str, separator: &str) -> Result<(&'a str, &'a str)> {
+    s.split_once(separator)
+        .map(|(left, right)| (left.trim(), right.trim()))
+        .ok_or_else(|| ParseError::new(s, format!("{separator:?}")))
+}
+
+/// What follows `prefix`, trimmed.
+pub fn prefixed<'a>(s: &'a str, prefix: &str) -> Result<&'a str> {
+    s.trim_start()
+        .strip_prefix(prefix)
+        .map(str::trim)
+        .ok_or_else(|| ParseError::new(s, format!("{prefix:?}")))
+}
+
+/// What sits between `open` and `close`, trimmed.
+pub fn enclosed<'a>(s: &'a str, open: &str, close: &str) -> Result<&'a str> {
+    let s = s.trim();
+    s.strip_prefix(open)
+        .and_then(|inner| inner.strip_suffix(close))
+        .map(str::trim)
+        .ok_or_else(|| ParseError::new(s, format!("something between {open:?} and {close:?}")))
+}
+
+/// A section headed `label:`, such as `seeds: 79 14 55 13`. Returns what follows the colon,
+/// trimmed, which may run over several lines.
+pub fn labelled<'a>(s: &'a str, label: &str) -> Result<&'a str> {
+    s.trim_start()
+        .strip_prefix(label)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .map(str::trim)
+        .ok_or_else(|| ParseError::new(s, format!("a {label:?} section")))
+}
+
+/// A `key: value` line, both sides trimmed.
+pub fn key_value(line: &str) -> Result<(&str, &str)> {
+    let (key, value) = split_pair(line, ":")?;
+    if key.is_empty() {
+        return Err(ParseError::new(line, "a key before the colon"));
+    }
+    Ok((key, value))
+}
+
+/// Every non-blank line as a `key: value` pair.
+pub fn key_values(s: &str) -> Result<Vec<(&str, &str)>> {
+    s.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(key_value)
+        .collect()
+}
+
+/// Runs of lines separated by one or more blank lines, without their trailing newlines.
+pub fn blocks(s: &str) -> Vec<&str> {
+    let mut blocks = Vec::new();
+    let mut start = None;
+    let mut offset = 0;
+    for line in s.split_inclusive('\n') {
+        let blank = line.trim().is_empty();
+        match (start, blank) {
+            (None, false) => start = Some(offset),
+            (Some(from), true) => {
+                blocks.push(s[from..offset].trim_end());
+                start = None;
+            }
+            _ => {}
+        }
+        offset += line.len();
+    }
+    if let Some(from) = start {
+        blocks.push(s[from..].trim_end());
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        blocks, enclosed, key_values, labelled, list, number, numbers, prefixed, signed,
+        split_pair, ParseError,
+    };
+
+    #[test]
+    fn parses_numbers() {
+        assert_eq!(number::<u32>(" 42 "), Ok(42));
+        assert_eq!(signed::<i32>("-7"), Ok(-7));
+        assert_eq!(signed::<i32>("+7"), Ok(7));
+        assert_eq!(numbers::<u8>("1  2\t3"), Ok(vec![1, 2, 3]));
+
+        assert_eq!(number::<u32>("-7").unwrap_err().expected, "a number");
+        assert_eq!(number::<u32>("").unwrap_err().expected, "a number");
+        assert!(signed::<i32>("+-7").is_err());
+        assert!(signed::<i32>("-").is_err());
+        assert_eq!(
+            number::<u8>("256").unwrap_err().expected,
+            "a number small enough to hold"
+        );
+    }
+
+    #[test]
+    fn locates_errors_deep_in_the_input() {
+        let input = "Game 1: 3 blue\nGame 2: 4 red, x green";
+        let line = input.lines().nth(1).unwrap();
+        let (_, cubes) = split_pair(line, ":").unwrap();
+        let err = list(cubes, ",", |cube| {
+            let (n, _) = split_pair(cube, " ")?;
+            number::<u32>(n)
+        })
+        .unwrap_err();
+
+        assert_eq!(err.location, None);
+        assert_eq!(err.clone().locate(line).location, Some((1, 16)));
+        let err = err.locate(input);
+        assert_eq!(err.location, Some((2, 16)));
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 16: expected a number, found \"x\""
+        );
+
+        // Text from somewhere else can't be located
+        let elsewhere = ParseError::new("x", "y").locate(input);
+        assert_eq!(elsewhere.location, None);
+    }
+
+    #[test]
+    fn picks_apart_labels_and_delimiters() {
+        assert_eq!(labelled("Time:  7 15", "Time"), Ok("7 15"));
+        assert!(labelled("Distance: 9", "Time").is_err());
+        assert_eq!(prefixed("Card  12", "Card"), Ok("12"));
+        assert_eq!(enclosed(" (BBB, CCC) ", "(", ")"), Ok("BBB, CCC"));
+        assert!(enclosed("(BBB, CCC", "(", ")").is_err());
+        assert_eq!(
+            key_values("a: 1\n\nb:2\n"),
+            Ok(vec![("a", "1"), ("b", "2")])
+        );
+        assert!(key_values(": 1").is_err());
+    }
+
+    #[test]
+    fn splits_on_blank_lines() {
+        assert_eq!(blocks("a\nb\n\n\n  \nc\n\nd\n"), vec!["a\nb", "c", "d"]);
+        assert!(blocks("\n\n").is_empty());
+    }
+}
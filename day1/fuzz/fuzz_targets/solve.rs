@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    for result in [day1::solve_part_one(input), day1::solve_part_two(input)] {
+        if let Err(err) = result {
+            assert!(err.location.is_some(), "{err:?}");
+        }
+    }
+});
@@ -0,0 +1,57 @@
+//! Made-up calibration documents of any size, with answers worked out from how each line was
+//! put together rather than by reading it back.
+
+use aoc::rng::Rng;
+
+const WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// None of these letters turn up in any of the words, so filler can never spell a digit.
+const FILLER: &[u8] = b"abcdjklmpqy";
+
+fn filler(rng: &mut Rng, line: &mut String, min: usize) {
+    for _ in 0..min + rng.below(3) {
+        line.push(*rng.pick(FILLER) as char);
+    }
+}
+
+/// `size` lines, and the answers to both parts for them.
+pub fn generate(size: usize, seed: u64) -> (String, usize, usize) {
+    let mut rng = Rng::new(seed);
+    let mut lines = Vec::with_capacity(size);
+    let (mut part_one, mut part_two) = (0, 0);
+
+    for _ in 0..size {
+        // Each digit with whether it was written out as a word. At least one has to be a
+        // plain digit for part one to have an answer.
+        let mut digits: Vec<(usize, bool)> = (0..1 + rng.below(6))
+            .map(|_| (1 + rng.below(9), rng.one_in(2)))
+            .collect();
+        let plain = rng.below(digits.len());
+        digits[plain].1 = false;
+
+        let mut line = String::new();
+        filler(&mut rng, &mut line, 0);
+        for (i, (digit, spelled)) in digits.iter().enumerate() {
+            if i > 0 {
+                // Keep words apart so they can't share letters, as in `twone`
+                filler(&mut rng, &mut line, 1);
+            }
+            match spelled {
+                true => line.push_str(WORDS[digit - 1]),
+                false => line.push_str(&digit.to_string()),
+            }
+        }
+        filler(&mut rng, &mut line, 0);
+        lines.push(line);
+
+        let mut plain = digits.iter().filter(|(_, spelled)| !spelled);
+        let first = plain.next().map_or(0, |(d, _)| *d);
+        let last = plain.next_back().map_or(first, |(d, _)| *d);
+        part_one += first * 10 + last;
+        part_two += digits[0].0 * 10 + digits[digits.len() - 1].0;
+    }
+
+    (lines.join("\n"), part_one, part_two)
+}
@@ -0,0 +1,168 @@
+use aoc::parse::ParseError;
+
+pub mod generate;
+
+/// Fails on the first line without a digit, since it has no calibration value.
+pub fn solve_part_one(input: &str) -> Result<usize, ParseError> {
+    input
+        .lines()
+        .map(|line| {
+            let (first, last) = get_first_and_last_int(digits(line))
+                .ok_or_else(|| ParseError::new(line, "a line with a digit").locate(input))?;
+            Ok(combine_numbers((first, last)))
+        })
+        .sum()
+}
+
+/// Fails on the first line without a digit, written out or not.
+pub fn solve_part_two(input: &str) -> Result<usize, ParseError> {
+    input
+        .lines()
+        .map(|line| {
+            let first_int = find_from_start(line).ok_or_else(|| {
+                ParseError::new(line, "a line with a digit or a digit's name").locate(input)
+            })?;
+            let last_int = find_from_end(line).unwrap_or(first_int);
+            Ok(combine_numbers((first_int, last_int)))
+        })
+        .sum()
+}
+
+/// The digits in a line, read straight off the characters.
+fn digits(line: &str) -> impl Iterator<Item = usize> + '_ {
+    line.chars()
+        .filter_map(|c| c.to_digit(10))
+        .map(|d| d as usize)
+}
+
+fn get_first_and_last_int(mut nums: impl Iterator<Item = usize>) -> Option<(usize, usize)> {
+    let first = nums.next()?;
+    let last = nums.last().unwrap_or(first);
+    Some((first, last))
+}
+
+fn combine_numbers((first, last): (usize, usize)) -> usize {
+    first * 10 + last
+}
+
+const CASES: [(&str, usize); 20] = [
+    ("zero", 0),
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+    ("0", 0),
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("4", 4),
+    ("5", 5),
+    ("6", 6),
+    ("7", 7),
+    ("8", 8),
+    ("9", 9),
+];
+
+fn find_from_start(mut line: &str) -> Option<usize> {
+    while !line.is_empty() {
+        let found_num = CASES.iter().find(|(str, _)| line.starts_with(str));
+
+        if let Some((_, num)) = found_num {
+            return Some(*num);
+        }
+
+        let mut chars = line.chars();
+        chars.next();
+        line = chars.as_str();
+    }
+
+    None
+}
+
+fn find_from_end(mut line: &str) -> Option<usize> {
+    while !line.is_empty() {
+        let found_num = CASES.iter().find(|(str, _)| line.ends_with(str));
+
+        if let Some((_, num)) = found_num {
+            return Some(*num);
+        }
+
+        let mut chars = line.chars();
+        chars.next_back();
+        line = chars.as_str();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc::{
+        alloc::{allocations, Counting},
+        input::{as_windows, normalize},
+    };
+
+    use crate::{generate::generate, solve_part_one, solve_part_two};
+
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+
+    #[test]
+    fn solves_the_samples() {
+        assert_eq!(
+            solve_part_one("1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet"),
+            Ok(142)
+        );
+        assert_eq!(solve_part_two(include_str!("../sample.txt")), Ok(281));
+    }
+
+    #[test]
+    fn agrees_with_generated_inputs() {
+        for seed in 0..20 {
+            let (input, part_one, part_two) = generate(200, seed);
+            assert_eq!(solve_part_one(&input), Ok(part_one), "seed {seed}");
+            assert_eq!(solve_part_two(&input), Ok(part_two), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn copes_with_any_text() {
+        // Both used to panic, on a line without digits and on slicing through the `é`
+        assert!(solve_part_two("nothing here").is_err());
+        assert_eq!(solve_part_two("é7é\nsix\u{1f600}"), Ok(77 + 66));
+    }
+
+    #[test]
+    fn names_the_line_without_a_digit() {
+        let err = solve_part_one("1abc2\nsix\n7").unwrap_err();
+        assert_eq!((err.found.as_str(), err.location), ("six", Some((2, 1))));
+        assert_eq!(solve_part_two("1abc2\nsix\n7"), Ok(12 + 66 + 77));
+
+        let err = solve_part_two("1abc2\n\nxyz").unwrap_err();
+        assert_eq!((err.found.as_str(), err.location), ("", Some((2, 1))));
+    }
+
+    #[test]
+    fn line_endings_make_no_difference() {
+        let sample = include_str!("../sample.txt");
+        let windows = as_windows(sample);
+        assert_eq!(
+            solve_part_two(&normalize(&windows)),
+            solve_part_two(&normalize(sample))
+        );
+    }
+
+    #[test]
+    fn never_allocates_per_character() {
+        let input = "a1b2c3d4e5f\n".repeat(1000);
+        let (answer, count) = allocations(|| solve_part_one(&input));
+        assert_eq!(answer, Ok(15_000));
+        assert_eq!(count, 0);
+        let (_, count) = allocations(|| solve_part_two(&input));
+        assert_eq!(count, 0);
+    }
+}
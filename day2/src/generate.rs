@@ -0,0 +1,40 @@
+//! Made-up cube games of any size, with answers worked out from the draws as they're made.
+
+use aoc::rng::Rng;
+
+const COLOURS: [&str; 3] = ["red", "green", "blue"];
+const LIMITS: [u64; 3] = [12, 13, 14];
+
+/// `size` games, and the answers to both parts for them.
+pub fn generate(size: usize, seed: u64) -> (String, u64, u64) {
+    let mut rng = Rng::new(seed);
+    let mut lines = Vec::with_capacity(size);
+    let (mut part_one, mut part_two) = (0, 0);
+
+    for id in 1..=size as u64 {
+        let mut most = [0; 3];
+        let mut turns = Vec::new();
+        for _ in 0..1 + rng.below(6) {
+            let mut colours = [0, 1, 2];
+            rng.shuffle(&mut colours);
+            let shown = &colours[..1 + rng.below(3)];
+            let cubes: Vec<String> = shown
+                .iter()
+                .map(|&colour| {
+                    let count = 1 + rng.below(20) as u64;
+                    most[colour] = most[colour].max(count);
+                    format!("{count} {}", COLOURS[colour])
+                })
+                .collect();
+            turns.push(cubes.join(", "));
+        }
+        lines.push(format!("Game {id}: {}", turns.join("; ")));
+
+        if most.iter().zip(LIMITS).all(|(n, limit)| *n <= limit) {
+            part_one += id;
+        }
+        part_two += most.iter().product::<u64>();
+    }
+
+    (lines.join("\n"), part_one, part_two)
+}
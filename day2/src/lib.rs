@@ -0,0 +1,178 @@
+use std::str::FromStr;
+
+pub mod generate;
+
+use aoc::parse::{list, number, prefixed, split_pair, ParseError};
+
+#[derive(Debug)]
+pub struct Game {
+    id: u32,
+    turns: Vec<Turn>,
+}
+
+impl Game {
+    fn get_max_red(&self) -> Option<u32> {
+        self.turns.iter().map(|t| t.red.unwrap_or(0)).max()
+    }
+    fn get_max_green(&self) -> Option<u32> {
+        self.turns.iter().map(|t| t.green.unwrap_or(0)).max()
+    }
+    fn get_max_blue(&self) -> Option<u32> {
+        self.turns.iter().map(|t| t.blue.unwrap_or(0)).max()
+    }
+    /// Three `u32` counts multiplied together always fit in a `u128`.
+    fn get_minimal_cube_power(&self) -> u128 {
+        let red = self.get_max_red().unwrap_or(1) as u128;
+        let green = self.get_max_green().unwrap_or(1) as u128;
+        let blue = self.get_max_blue().unwrap_or(1) as u128;
+
+        red * green * blue
+    }
+}
+
+impl IsValid for Game {
+    fn is_valid(&self) -> bool {
+        self.turns.iter().all(|t| t.is_valid())
+    }
+}
+
+#[derive(Default, Debug)]
+struct Turn {
+    red: Option<u32>,
+    green: Option<u32>,
+    blue: Option<u32>,
+}
+
+impl FromStr for Turn {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut new_turn = Turn::default();
+        for cube in s.split(',').map(str::trim) {
+            let (num, color) = split_pair(cube, " ")?;
+            let num = number(num)?;
+            match color {
+                "green" => new_turn.green = Some(num),
+                "red" => new_turn.red = Some(num),
+                "blue" => new_turn.blue = Some(num),
+                _ => return Err(ParseError::new(color, "red, green or blue")),
+            };
+        }
+        Ok(new_turn)
+    }
+}
+
+impl IsValid for Turn {
+    fn is_valid(&self) -> bool {
+        let mut valid_red = true;
+        let mut valid_green = true;
+        let mut valid_blue = true;
+
+        if let Some(red) = self.red {
+            valid_red = red <= 12;
+        }
+        if let Some(green) = self.green {
+            valid_green = green <= 13;
+        }
+        if let Some(blue) = self.blue {
+            valid_blue = blue <= 14;
+        }
+        valid_red && valid_green && valid_blue
+    }
+}
+
+trait IsValid {
+    fn is_valid(&self) -> bool;
+}
+
+impl FromStr for Game {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = || {
+            let (game_id, game) = split_pair(s, ":")?;
+            let id = number(prefixed(game_id, "Game")?)?;
+            let turns = list(game, ";", str::parse)?;
+            Ok(Game { id, turns })
+        };
+        parse().map_err(|e: ParseError| e.locate(s))
+    }
+}
+
+fn parse_games(input: &str) -> Result<Vec<Game>, ParseError> {
+    input
+        .lines()
+        .map(|line| line.parse().map_err(|e: ParseError| e.locate(input)))
+        .collect()
+}
+
+pub fn solve_part_one(input: &str) -> Result<u64, ParseError> {
+    Ok(parse_games(input)?
+        .iter()
+        .filter(|game| game.is_valid())
+        .map(|game| game.id as u64)
+        .sum())
+}
+
+pub fn solve_part_two(input: &str) -> Result<u128, ParseError> {
+    Ok(parse_games(input)?
+        .iter()
+        .map(Game::get_minimal_cube_power)
+        .sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc::input::{as_windows, normalize};
+
+    use crate::{generate::generate, solve_part_one, solve_part_two, Game};
+
+    #[test]
+    fn solves_the_sample() {
+        let sample = include_str!("./sample.txt");
+        assert_eq!(solve_part_one(sample), Ok(8));
+        assert_eq!(solve_part_two(sample), Ok(2286));
+    }
+
+    #[test]
+    fn agrees_with_generated_inputs() {
+        for seed in 0..20 {
+            let (input, part_one, part_two) = generate(200, seed);
+            assert_eq!(solve_part_one(&input), Ok(part_one), "seed {seed}");
+            assert_eq!(solve_part_two(&input), Ok(part_two.into()), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn line_endings_make_no_difference() {
+        let windows = normalize(&as_windows(include_str!("./sample.txt"))).into_owned();
+        assert_eq!(solve_part_one(&windows), Ok(8));
+        assert_eq!(solve_part_two(&windows), Ok(2286));
+    }
+
+    #[test]
+    fn says_where_a_game_is_broken() {
+        let err = "Game 4: 1 red; 2 blue, 3 purple"
+            .parse::<Game>()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 26: expected red, green or blue, found \"purple\""
+        );
+        assert!("Gane 4: 1 red".parse::<Game>().is_err());
+        assert!("Game 4 1 red".parse::<Game>().is_err());
+    }
+
+    #[test]
+    fn reports_bad_games_instead_of_panicking() {
+        let err = solve_part_one("Game 1: 3 red\nGame 2: x red").unwrap_err();
+        assert_eq!(err.location, Some((2, 9)));
+
+        assert_eq!(
+            solve_part_two("Game 1: 70000 red, 1 green, 1 blue"),
+            Ok(70000)
+        );
+        // Plenty to overflow the `u32` powers used to be
+        let huge = "Game 1: 4294967295 red, 4294967295 green, 4294967295 blue";
+        assert_eq!(solve_part_two(huge), Ok((u32::MAX as u128).pow(3)));
+        assert!(solve_part_two("Game 1: 4294967296 red").is_err());
+    }
+}
@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|line: &str| {
+    if let Err(err) = day3::parse_line(line, 0) {
+        assert!(err.clone().locate(line).location.is_some(), "{err:?}");
+    }
+});
@@ -0,0 +1,89 @@
+//! Made-up engine schematics of any size, with answers worked out by scanning the grid cell by
+//! cell rather than by the solver's number-against-symbol comparisons.
+
+use std::collections::{HashMap, HashSet};
+
+use aoc::rng::Rng;
+
+const SYMBOLS: &[u8] = b"*#+$/=%@&-";
+
+/// A `size` by `size` schematic, and the answers to both parts for it.
+pub fn generate(size: usize, seed: u64) -> (String, isize, isize) {
+    let mut rng = Rng::new(seed);
+    let mut grid = vec![vec![b'.'; size]; size];
+    for row in grid.iter_mut() {
+        let mut col = 0;
+        while col < size {
+            if rng.one_in(4) {
+                let digits = (1 + rng.below(3)).min(size - col);
+                for cell in &mut row[col..col + digits] {
+                    *cell = b'0' + rng.below(10) as u8;
+                }
+                // A number can't run straight into the next one
+                col += digits + 1;
+            } else {
+                if rng.one_in(5) {
+                    // Gears are what part two is about, so make plenty of them
+                    row[col] = if rng.one_in(2) {
+                        b'*'
+                    } else {
+                        *rng.pick(SYMBOLS)
+                    };
+                }
+                col += 1;
+            }
+        }
+    }
+
+    let text = grid
+        .iter()
+        .map(|row| String::from_utf8_lossy(row).into_owned())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let (part_one, part_two) = reference(&grid);
+    (text, part_one, part_two)
+}
+
+fn reference(grid: &[Vec<u8>]) -> (isize, isize) {
+    let mut part_one = 0;
+    let mut gears: HashMap<(usize, usize), Vec<isize>> = HashMap::new();
+
+    for (y, row) in grid.iter().enumerate() {
+        let mut x = 0;
+        while x < row.len() {
+            if !row[x].is_ascii_digit() {
+                x += 1;
+                continue;
+            }
+            let start = x;
+            let mut value = 0;
+            while x < row.len() && row[x].is_ascii_digit() {
+                value = value * 10 + (row[x] - b'0') as isize;
+                x += 1;
+            }
+
+            let rows = y.saturating_sub(1)..=(y + 1).min(grid.len() - 1);
+            let neighbours: HashSet<(usize, usize)> = rows
+                .flat_map(|ny| {
+                    (start.saturating_sub(1)..=x.min(row.len() - 1)).map(move |nx| (ny, nx))
+                })
+                .filter(|&(ny, nx)| grid[ny][nx] != b'.' && !grid[ny][nx].is_ascii_digit())
+                .collect();
+            if !neighbours.is_empty() {
+                part_one += value;
+            }
+            for (ny, nx) in neighbours {
+                if grid[ny][nx] == b'*' {
+                    gears.entry((ny, nx)).or_default().push(value);
+                }
+            }
+        }
+    }
+
+    let part_two = gears
+        .values()
+        .filter(|numbers| numbers.len() == 2)
+        .map(|numbers| numbers[0] * numbers[1])
+        .sum();
+    (part_one, part_two)
+}
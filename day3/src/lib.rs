@@ -0,0 +1,297 @@
+use aoc::parse::{number, ParseError};
+
+pub mod generate;
+
+fn parse_input(input: &str) -> Result<(Vec<Symbol>, Vec<Number>), ParseError> {
+    let mut symbols: Vec<Symbol> = vec![];
+    let mut numbers: Vec<Number> = vec![];
+    for (row, line) in input.lines().enumerate() {
+        let (mut syms, mut nums) = parse_line(line, row as isize).map_err(|e| e.locate(input))?;
+        symbols.append(&mut syms);
+        numbers.append(&mut nums);
+    }
+    Ok((symbols, numbers))
+}
+
+/// Every symbol and number in a row of the schematic. Columns count characters, not bytes.
+pub fn parse_line(mut line: &str, row: isize) -> Result<(Vec<Symbol>, Vec<Number>), ParseError> {
+    let mut symbols: Vec<Symbol> = vec![];
+    let mut numbers: Vec<Number> = vec![];
+    let mut col = 0;
+    while let Some(c) = line.chars().next() {
+        if c.is_ascii_digit() {
+            let num_string = parse_number_from_line(line);
+            let parsed_number = number::<isize>(num_string)?;
+            numbers.push(Number {
+                value: parsed_number,
+                start: Point { x: col, y: row },
+                end: Point {
+                    x: col + num_string.len() as isize - 1,
+                    y: row,
+                },
+            });
+            col += num_string.len() as isize;
+            line = &line[num_string.len()..];
+        } else {
+            if c.is_symbol() {
+                symbols.push(Symbol {
+                    location: Point { x: col, y: row },
+                    symbol: c,
+                });
+            }
+            col += 1;
+            line = &line[c.len_utf8()..];
+        }
+    }
+    Ok((symbols, numbers))
+}
+
+fn parse_number_from_line(input: &str) -> &str {
+    let len = input.bytes().take_while(u8::is_ascii_digit).count();
+    &input[..len]
+}
+
+/// Summed as an `i128`, which no schematic holds enough `isize` part numbers to overflow.
+pub fn solve_part_one(input: &str) -> Result<i128, ParseError> {
+    let (symbols, numbers) = parse_input(input)?;
+    let score = numbers
+        .iter()
+        .filter_map(|num| {
+            if num.has_adjacent_symbol(&symbols) {
+                return Some(num.value as i128);
+            }
+            None
+        })
+        .sum::<i128>();
+
+    Ok(score)
+}
+
+/// Two `isize` part numbers multiplied together always fit in an `i128`.
+pub fn solve_part_two(input: &str) -> Result<i128, ParseError> {
+    let (symbols, numbers) = parse_input(input)?;
+    let gear_symbols: Vec<_> = symbols.iter().filter(|s| s.symbol == '*').collect();
+
+    let gears = gear_symbols
+        .iter()
+        .filter_map(|s| {
+            let touching = s.find_touching_numbers(&numbers);
+            if touching.len() == 2 {
+                return Some(touching);
+            }
+            None
+        })
+        .collect::<Vec<Vec<isize>>>();
+
+    let gear_ratios: i128 = gears
+        .iter()
+        .map(|g| g.iter().map(|n| *n as i128).product::<i128>())
+        .sum();
+    Ok(gear_ratios)
+}
+
+trait IsSymbol {
+    fn is_symbol(&self) -> bool;
+}
+
+impl IsSymbol for char {
+    fn is_symbol(&self) -> bool {
+        self != &'.' && !self.is_ascii_digit()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Point {
+    x: isize,
+    y: isize,
+}
+
+#[derive(Debug)]
+pub struct Symbol {
+    location: Point,
+    symbol: char,
+}
+
+impl Symbol {
+    fn find_left_number(&self, numbers: &[Number]) -> Option<isize> {
+        let found = numbers.iter().find(|num| {
+            let is_same_y = num.end.y == self.location.y;
+            let is_touching_left = num.start.x - self.location.x == 1;
+            is_same_y && is_touching_left
+        });
+        if let Some(f) = found {
+            return Some(f.value);
+        }
+        None
+    }
+
+    fn find_right_number(&self, numbers: &[Number]) -> Option<isize> {
+        let found = numbers.iter().find(|num| {
+            let is_same_y = num.end.y == self.location.y;
+            let is_touching_right = self.location.x - num.end.x == 1;
+            is_same_y && is_touching_right
+        });
+        if let Some(f) = found {
+            return Some(f.value);
+        }
+        None
+    }
+
+    fn find_upper_number(&self, numbers: &[Number]) -> Vec<isize> {
+        numbers
+            .iter()
+            .filter_map(|num| {
+                let is_above = self.location.y - num.start.y == 1;
+                let range = num.start.x - 1..=num.end.x + 1;
+                let is_overlapping = range.contains(&self.location.x);
+                if is_above && is_overlapping {
+                    return Some(num.value);
+                }
+                None
+            })
+            .collect()
+    }
+
+    fn find_lower_number(&self, numbers: &[Number]) -> Vec<isize> {
+        numbers
+            .iter()
+            .filter_map(|num| {
+                let is_below = num.start.y - self.location.y == 1;
+                let range = num.start.x - 1..=num.end.x + 1;
+                let is_overlapping = range.contains(&self.location.x);
+                if is_below && is_overlapping {
+                    return Some(num.value);
+                }
+                None
+            })
+            .collect()
+    }
+
+    fn find_touching_numbers(&self, numbers: &[Number]) -> Vec<isize> {
+        let mut touching = vec![];
+        let left_side = self.find_left_number(numbers);
+        let right_side = self.find_right_number(numbers);
+        let mut upper_numbers = self.find_upper_number(numbers);
+        let mut lower_numbers = self.find_lower_number(numbers);
+
+        if let Some(left) = left_side {
+            touching.push(left);
+        }
+        if let Some(right) = right_side {
+            touching.push(right);
+        }
+
+        touching.append(&mut upper_numbers);
+        touching.append(&mut lower_numbers);
+
+        touching
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Number {
+    value: isize,
+    start: Point,
+    end: Point,
+}
+
+impl Number {
+    fn has_adjacent_symbol(&self, symbols: &[Symbol]) -> bool {
+        symbols
+            .iter()
+            .find(|symbol| {
+                let is_right = symbol.location.x - self.end.x == 1;
+                let is_left = self.start.x - symbol.location.x == 1;
+                let is_above = self.start.y - symbol.location.y == 1;
+                let is_below = symbol.location.y - self.end.y == 1;
+
+                let is_top_right_corner = is_right && is_above;
+                let is_top_left_corner = is_left && is_above;
+                let is_bottom_right_corner = is_right && is_below;
+                let is_bottom_left_corner = is_left && is_below;
+
+                let is_between =
+                    self.start.x <= symbol.location.x && symbol.location.x <= self.end.x;
+
+                let is_same_line = self.start.y == symbol.location.y;
+
+                let is_between_above = is_above && is_between;
+                let is_between_below = is_below && is_between;
+
+                let is_right_same_line = is_same_line && is_right;
+                let is_left_same_line = is_same_line && is_left;
+
+                is_top_left_corner
+                    || is_top_right_corner
+                    || is_bottom_left_corner
+                    || is_bottom_right_corner
+                    || is_between_above
+                    || is_between_below
+                    || is_right_same_line
+                    || is_left_same_line
+            })
+            .is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc::input::{as_windows, normalize};
+
+    use crate::{generate::generate, parse_line, solve_part_one, solve_part_two};
+
+    const SAMPLE: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+    #[test]
+    fn agrees_with_generated_inputs() {
+        for seed in 0..20 {
+            let (input, part_one, part_two) = generate(40, seed);
+            assert_eq!(solve_part_one(&input), Ok(part_one as i128), "seed {seed}");
+            assert_eq!(solve_part_two(&input), Ok(part_two as i128), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn only_gears_between_exactly_two_numbers_count() {
+        assert_eq!(solve_part_two("1.2\n.*.\n3.."), Ok(0));
+        assert_eq!(solve_part_two("1.2\n.*.\n..."), Ok(2));
+    }
+
+    #[test]
+    fn line_endings_make_no_difference() {
+        let windows = normalize(&as_windows(SAMPLE)).into_owned();
+        for input in [SAMPLE, &windows] {
+            assert_eq!(solve_part_one(input), Ok(4361));
+            assert_eq!(solve_part_two(input), Ok(467835));
+        }
+    }
+
+    #[test]
+    fn counts_columns_in_characters() {
+        // Anything that isn't a digit or a dot is a symbol, `é` included
+        let (symbols, numbers) = parse_line("é*12", 0).unwrap();
+        assert_eq!((symbols[1].location.x, numbers[0].start.x), (1, 2));
+        assert_eq!(solve_part_one("é.12\n..\u{1f600}.3"), Ok(12));
+    }
+
+    #[test]
+    fn takes_part_numbers_up_to_isize_max() {
+        assert_eq!(solve_part_one("123456*"), Ok(123456));
+        let max = format!("{0}*{0}", isize::MAX);
+        assert_eq!(solve_part_one(&max), Ok(2 * isize::MAX as i128));
+        assert_eq!(solve_part_two(&max), Ok((isize::MAX as i128).pow(2)));
+
+        let err = solve_part_one("1*\n.99999999999999999999").unwrap_err();
+        assert_eq!(err.location, Some((2, 2)));
+        assert_eq!(err.expected, "a number small enough to hold");
+    }
+}
@@ -0,0 +1,12 @@
+#![no_main]
+
+use day4::Policy;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(cards) = day4::parse_input(input) {
+        day4::solve_part_one(&cards);
+        let _ = day4::solve_part_two(&cards, Policy::Clamp);
+        let _ = day4::solve_part_two(&cards, Policy::Reject);
+    }
+});
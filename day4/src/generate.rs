@@ -0,0 +1,53 @@
+//! Made-up piles of scratchcards of any size, with answers worked out from how many numbers
+//! each card was dealt to match.
+
+use aoc::rng::Rng;
+
+/// `size` cards, and the answers to both parts for them.
+pub fn generate(size: usize, seed: u64) -> (String, usize, usize) {
+    let mut rng = Rng::new(seed);
+    let mut numbers: Vec<usize> = (1..100).collect();
+    let mut lines = Vec::with_capacity(size);
+    let mut matches = Vec::with_capacity(size);
+
+    // Copies pile up exponentially, so no card wins copies past the end of its run. Each run
+    // starts over from one copy apiece and the answer grows with the size, not past a `usize`.
+    let mut run_end = 0;
+    for id in 1..=size {
+        if id > run_end {
+            run_end = (id + rng.below(30)).min(size);
+        }
+        let won = if rng.one_in(3) {
+            (1 + rng.below(10)).min(run_end - id)
+        } else {
+            0
+        };
+        matches.push(won);
+
+        rng.shuffle(&mut numbers);
+        let winners = &numbers[..10];
+        let mut mine: Vec<usize> = winners[..won].to_vec();
+        mine.extend(&numbers[10..35 - won]);
+        rng.shuffle(&mut mine);
+
+        let show = |numbers: &[usize]| {
+            let numbers: Vec<String> = numbers.iter().map(|n| format!("{n:>2}")).collect();
+            numbers.join(" ")
+        };
+        lines.push(format!("Card {id:>3}: {} | {}", show(winners), show(&mine)));
+    }
+
+    let part_one = matches
+        .iter()
+        .filter(|&&won| won > 0)
+        .map(|won| 1 << (won - 1))
+        .sum();
+    // Working backwards, each card is worth itself plus whatever the cards it copies are worth
+    let mut worth = vec![0; size];
+    for i in (0..size).rev() {
+        worth[i] = 1 + worth[i + 1..=i + matches[i]].iter().sum::<usize>();
+    }
+    let part_two = worth.iter().sum();
+
+    (lines.join("\n"), part_one, part_two)
+}
@@ -0,0 +1,335 @@
+use std::{collections::HashSet, ops::RangeInclusive, str::FromStr};
+
+use aoc::{
+    counter::Counter,
+    math::checked_pow,
+    parse::{number, numbers, prefixed, split_pair, ParseError},
+};
+
+pub mod generate;
+
+#[derive(Debug)]
+pub struct Card {
+    id: usize,
+    winners: Vec<usize>,
+    repeated: Vec<usize>,
+}
+
+/// Something about the pile of cards that doesn't line up with the puzzle rules.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Diagnostic {
+    DuplicateId(usize),
+    /// A run of ids with no card, reported as a whole so a card numbered in the billions
+    /// doesn't mean billions of diagnostics.
+    MissingIds(RangeInclusive<usize>),
+    CopiesPastEnd {
+        id: usize,
+        last: usize,
+    },
+    RepeatedNumber {
+        id: usize,
+        number: usize,
+    },
+    /// There'd be more copies than a `usize` can count. An error whatever the policy.
+    TooManyCopies,
+}
+
+/// Everything that can keep the input from giving an answer to both parts.
+#[derive(Debug, PartialEq)]
+pub enum PuzzleError {
+    Syntax(ParseError),
+    Pile(Vec<Diagnostic>),
+}
+
+impl From<ParseError> for PuzzleError {
+    fn from(err: ParseError) -> Self {
+        PuzzleError::Syntax(err)
+    }
+}
+
+impl From<Vec<Diagnostic>> for PuzzleError {
+    fn from(diagnostics: Vec<Diagnostic>) -> Self {
+        PuzzleError::Pile(diagnostics)
+    }
+}
+
+/// What to do when the pile has problems: clamp copies to the cards that exist, or refuse to
+/// count anything at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    Clamp,
+    Reject,
+}
+
+impl Card {
+    /// `None` when a card has so many winners its score won't fit in a `usize`.
+    fn get_score(&self) -> Option<usize> {
+        if self.winners.is_empty() {
+            return Some(0);
+        }
+
+        let exp = u32::try_from(self.winners.len() - 1).ok()?;
+        checked_pow(2, exp)
+    }
+}
+impl FromStr for Card {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = || {
+            let (id, sides) = split_pair(s, ":")?;
+            let id = number(prefixed(id, "Card")?)?;
+
+            let (goals, mine) = split_pair(sides, "|")?;
+            let goals = numbers(goals)?;
+            let mine = numbers(mine)?;
+            let winners = get_winners(&goals, &mine);
+
+            let mut repeated = find_repeated(&goals);
+            repeated.extend(find_repeated(&mine));
+
+            Ok(Card {
+                id,
+                winners,
+                repeated,
+            })
+        };
+        parse().map_err(|e: ParseError| e.locate(s))
+    }
+}
+
+fn find_repeated(numbers: &[usize]) -> Vec<usize> {
+    let mut seen = HashSet::new();
+    numbers
+        .iter()
+        .filter(|n| !seen.insert(**n))
+        .copied()
+        .collect()
+}
+
+pub fn parse_input(input: &str) -> Result<Vec<Card>, ParseError> {
+    input
+        .lines()
+        .map(|line| line.parse().map_err(|e: ParseError| e.locate(input)))
+        .collect()
+}
+
+fn get_winners(goals: &[usize], mine: &[usize]) -> Vec<usize> {
+    goals
+        .iter()
+        .filter_map(|g| {
+            if mine.contains(g) {
+                return Some(*g);
+            }
+            None
+        })
+        .collect()
+}
+
+/// Everything about the pile that breaks the puzzle rules, without stopping at the first.
+pub fn check_pile(cards: &[Card]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut ids = HashSet::new();
+    let last = cards.iter().map(|c| c.id).max().unwrap_or(0);
+
+    for card in cards {
+        if !ids.insert(card.id) {
+            diagnostics.push(Diagnostic::DuplicateId(card.id));
+        }
+        for number in &card.repeated {
+            diagnostics.push(Diagnostic::RepeatedNumber {
+                id: card.id,
+                number: *number,
+            });
+        }
+        if card.id.saturating_add(card.winners.len()) > last {
+            diagnostics.push(Diagnostic::CopiesPastEnd { id: card.id, last });
+        }
+    }
+
+    let mut ids: Vec<usize> = ids.into_iter().collect();
+    ids.sort_unstable();
+    let mut next = 1;
+    for id in ids {
+        if id > next {
+            diagnostics.push(Diagnostic::MissingIds(next..=id - 1));
+        }
+        next = next.max(id.saturating_add(1));
+    }
+
+    diagnostics
+}
+
+/// How many copies of each card end up in the pile, originals included.
+fn create_winner_dict(cards: &[Card], policy: Policy) -> Result<Counter<usize>, Vec<Diagnostic>> {
+    let diagnostics = check_pile(cards);
+    if policy == Policy::Reject && !diagnostics.is_empty() {
+        return Err(diagnostics);
+    }
+
+    let ids: HashSet<usize> = cards.iter().map(|c| c.id).collect();
+    let mut copies = Counter::new();
+
+    // A card only wins copies of cards after it, so all of its own copies have to be in
+    // before it's played, wherever it turned up in the input
+    let mut in_order: Vec<&Card> = cards.iter().collect();
+    in_order.sort_by_key(|card| card.id);
+
+    let too_many = || vec![Diagnostic::TooManyCopies];
+    for card in in_order {
+        let won = copies.checked_add_n(card.id, 1).ok_or_else(too_many)?;
+        let keys = (1..=card.winners.len()).filter_map(|k| card.id.checked_add(k));
+        for key in keys.filter(|k| ids.contains(k)) {
+            copies.checked_add_n(key, won).ok_or_else(too_many)?;
+        }
+    }
+
+    Ok(copies)
+}
+
+pub fn solve_part_one(cards: &[Card]) -> Option<usize> {
+    cards
+        .iter()
+        .try_fold(0_usize, |total, c| total.checked_add(c.get_score()?))
+}
+
+pub fn solve_part_two(cards: &[Card], policy: Policy) -> Result<usize, Vec<Diagnostic>> {
+    create_winner_dict(cards, policy)?
+        .checked_total()
+        .ok_or_else(|| vec![Diagnostic::TooManyCopies])
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc::input::{as_windows, normalize};
+
+    use crate::{
+        check_pile, generate::generate, parse_input, solve_part_one, solve_part_two, Card,
+        Diagnostic, Policy,
+    };
+
+    const SAMPLE: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+
+    fn cards(input: &str) -> Vec<Card> {
+        input.lines().map(|l| l.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn agrees_with_generated_inputs() {
+        for seed in 0..20 {
+            let (input, part_one, part_two) = generate(200, seed);
+            let cards = parse_input(&input).unwrap();
+            assert_eq!(check_pile(&cards), vec![], "seed {seed}");
+            assert_eq!(solve_part_one(&cards), Some(part_one), "seed {seed}");
+            assert_eq!(
+                solve_part_two(&cards, Policy::Reject),
+                Ok(part_two),
+                "seed {seed}"
+            );
+        }
+    }
+
+    #[test]
+    fn line_endings_make_no_difference() {
+        let windows = normalize(&as_windows(SAMPLE)).into_owned();
+        let tabbed = SAMPLE.replace("  ", "\t");
+        for input in [SAMPLE, &windows, &normalize(&tabbed)] {
+            let cards = parse_input(input).unwrap();
+            assert_eq!(solve_part_one(&cards), Some(13));
+            assert_eq!(solve_part_two(&cards, Policy::Reject), Ok(30));
+        }
+    }
+
+    #[test]
+    fn rejects_a_bad_id() {
+        assert!("Card x: 1 2 | 3 4".parse::<Card>().is_err());
+        assert!("1 2 | 3 4".parse::<Card>().is_err());
+    }
+
+    #[test]
+    fn points_at_the_bad_number() {
+        let err = "Card 3: 1 2 | 3 4x".parse::<Card>().unwrap_err();
+        assert_eq!(err.location, Some((1, 17)));
+        assert_eq!(err.found, "4x");
+    }
+
+    #[test]
+    fn scores_overflow_instead_of_wrapping() {
+        let pile = cards("Card 1: 1 2 3 | 1 2 3\nCard 2: 4 | 5");
+        assert_eq!(solve_part_one(&pile), Some(4));
+
+        let numbers: Vec<String> = (1..=70).map(|n| n.to_string()).collect();
+        let numbers = numbers.join(" ");
+        let pile = cards(&format!("Card 1: {numbers} | {numbers}"));
+        assert_eq!(pile[0].winners.len(), 70);
+        assert_eq!(solve_part_one(&pile), None);
+    }
+
+    #[test]
+    fn clamps_copies_past_the_last_card() {
+        let pile = cards("Card 1: 1 2 | 1 3\nCard 2: 3 4 | 3 5");
+        assert_eq!(
+            check_pile(&pile),
+            vec![Diagnostic::CopiesPastEnd { id: 2, last: 2 }]
+        );
+        // Card 1 wins a copy of card 2, and the copy card 2 would give to card 3 goes nowhere
+        assert_eq!(solve_part_two(&pile, Policy::Clamp), Ok(3));
+        assert!(solve_part_two(&pile, Policy::Reject).is_err());
+    }
+
+    #[test]
+    fn plays_cards_in_id_order() {
+        let pile = cards("Card 2: 5 | 5\nCard 3: 1 | 2\nCard 1: 1 | 1");
+        assert_eq!(check_pile(&pile), vec![]);
+        // Card 1 copies card 2, and both copies of card 2 copy card 3
+        assert_eq!(solve_part_two(&pile, Policy::Reject), Ok(6));
+    }
+
+    #[test]
+    fn reports_duplicate_and_missing_ids() {
+        let pile = cards("Card 1: 1 | 2\nCard 1: 1 | 2\nCard 3: 1 | 2");
+        assert_eq!(
+            check_pile(&pile),
+            vec![Diagnostic::DuplicateId(1), Diagnostic::MissingIds(2..=2)]
+        );
+    }
+
+    #[test]
+    fn reports_repeated_numbers() {
+        let pile = cards("Card 1: 5 5 | 6 7 7");
+        assert_eq!(
+            check_pile(&pile),
+            vec![
+                Diagnostic::RepeatedNumber { id: 1, number: 5 },
+                Diagnostic::RepeatedNumber { id: 1, number: 7 },
+            ]
+        );
+    }
+
+    #[test]
+    fn survives_absurd_piles() {
+        let far = cards("Card 2: 1 | 2\nCard 18446744073709551615: 1 2 | 1 2");
+        assert_eq!(
+            check_pile(&far),
+            vec![
+                Diagnostic::MissingIds(1..=1),
+                Diagnostic::MissingIds(3..=18446744073709551614),
+            ]
+        );
+        assert_eq!(solve_part_two(&far, Policy::Clamp), Ok(2));
+
+        // Every card copies the next two, so the copies grow like the Fibonacci numbers
+        let lines: Vec<String> = (1..=100)
+            .map(|id| format!("Card {id}: 1 2 | 1 2"))
+            .collect();
+        let pile = cards(&lines.join("\n"));
+        assert_eq!(
+            solve_part_two(&pile, Policy::Clamp),
+            Err(vec![Diagnostic::TooManyCopies])
+        );
+    }
+}
@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let Ok((seeds, almanac, seed_ranges)) = day5::parse_input(input) else {
+        return;
+    };
+    if let Ok(maps) = almanac.chain("seed", "location") {
+        day5::solve_part_one(&seeds, &maps);
+        day5::solve_part_two(seed_ranges, &maps);
+    }
+});
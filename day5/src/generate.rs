@@ -0,0 +1,93 @@
+//! Made-up almanacs of any size, with answers worked out by walking every single seed through
+//! every map, one range at a time.
+
+use aoc::rng::Rng;
+
+const CATEGORIES: [&str; 8] = [
+    "seed",
+    "soil",
+    "fertilizer",
+    "water",
+    "light",
+    "temperature",
+    "humidity",
+    "location",
+];
+
+/// `(dest, source, length)` lines.
+type Ranges = Vec<(i64, i64, i64)>;
+
+/// Cuts the numbers below `space` into about `pieces` runs and lays them back down shuffled,
+/// so every number lands somewhere and no two land in the same place, like the real maps.
+fn shuffled_map(rng: &mut Rng, space: i64, pieces: usize) -> Ranges {
+    let mut cuts: Vec<i64> = (0..pieces)
+        .map(|_| rng.range(1..space as u64) as i64)
+        .collect();
+    cuts.extend([0, space]);
+    cuts.sort_unstable();
+    cuts.dedup();
+
+    let mut runs: Vec<(i64, i64)> = cuts.windows(2).map(|w| (w[0], w[1] - w[0])).collect();
+    rng.shuffle(&mut runs);
+    let mut dest = 0;
+    let mut ranges = Vec::with_capacity(runs.len());
+    for (source, length) in runs {
+        ranges.push((dest, source, length));
+        dest += length;
+    }
+    rng.shuffle(&mut ranges);
+    ranges
+}
+
+fn convert(ranges: &Ranges, n: i64) -> i64 {
+    ranges
+        .iter()
+        .find(|(_, source, length)| (*source..source + length).contains(&n))
+        .map_or(n, |(dest, source, _)| n - source + dest)
+}
+
+/// An almanac with `size` seed ranges and about `size` lines in each map, and the answers to
+/// both parts for it.
+pub fn generate(size: usize, seed: u64) -> (String, i64, i64) {
+    let mut rng = Rng::new(seed);
+    let space = size.max(1) as i64 * 100;
+    let maps: Vec<Ranges> = (1..CATEGORIES.len())
+        .map(|_| shuffled_map(&mut rng, space, size))
+        .collect();
+
+    // Some seeds start past the end of the maps, where numbers go through unchanged
+    let seeds: Vec<(i64, i64)> = (0..size.max(1))
+        .map(|_| {
+            let start = rng.range(0..space as u64 * 11 / 10) as i64;
+            (start, 1 + rng.below(50) as i64)
+        })
+        .collect();
+
+    let location = |seed| maps.iter().fold(seed, |n, ranges| convert(ranges, n));
+    let part_one = seeds
+        .iter()
+        .flat_map(|&(start, length)| [start, length])
+        .map(location)
+        .min()
+        .unwrap();
+    let part_two = seeds
+        .iter()
+        .flat_map(|&(start, length)| start..start + length)
+        .map(location)
+        .min()
+        .unwrap();
+
+    let seeds: Vec<String> = seeds
+        .iter()
+        .map(|(start, length)| format!("{start} {length}"))
+        .collect();
+    let mut text = format!("seeds: {}", seeds.join(" "));
+    for (names, ranges) in CATEGORIES.windows(2).zip(&maps) {
+        text.push_str(&format!("\n\n{}-to-{} map:", names[0], names[1]));
+        for (dest, source, length) in ranges {
+            text.push_str(&format!("\n{dest} {source} {length}"));
+        }
+    }
+
+    (text, part_one, part_two)
+}
@@ -0,0 +1,474 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use anyhow::{anyhow, bail};
+use aoc::{
+    interval::{Interval, IntervalSet, PiecewiseMap},
+    parse::{blocks, labelled, numbers, split_pair, ParseError},
+};
+
+pub mod generate;
+
+#[derive(Debug)]
+pub struct Seed(i64);
+
+/// Converts numbers in the `from` category into numbers in the `to` category.
+#[derive(Debug)]
+pub struct Map<'a> {
+    from: &'a str,
+    to: &'a str,
+    pieces: PiecewiseMap,
+}
+
+/// Every map in the almanac, keyed by the categories it connects rather than where it showed
+/// up in the input.
+#[derive(Debug)]
+pub struct Almanac<'a>(Vec<Map<'a>>);
+
+#[derive(Debug)]
+struct Range {
+    dest: i64,
+    source: i64,
+    length: i64,
+}
+
+impl Range {
+    fn new(range: &str) -> Result<Self, ParseError> {
+        let [dest, source, length] = numbers::<i64>(range)?[..] else {
+            return Err(ParseError::new(range, "`dest source length`"));
+        };
+        // Both ends have to fit, otherwise shifting the domain over to `dest` overflows
+        if source.checked_add(length).is_none() || dest.checked_add(length).is_none() {
+            return Err(ParseError::new(range, "a range that ends before i64::MAX"));
+        }
+
+        Ok(Self {
+            dest,
+            source,
+            length,
+        })
+    }
+
+    fn domain(&self) -> Interval {
+        Interval::with_len(self.source, self.length)
+    }
+
+    fn offset(&self) -> i64 {
+        // Both are non-negative, so this can't overflow
+        self.dest - self.source
+    }
+}
+
+impl<'a> Map<'a> {
+    fn new(map: &'a str) -> Result<Self, ParseError> {
+        let (header, ranges) = map.split_once('\n').unwrap_or((map, ""));
+        let (from, to) = header
+            .strip_suffix(" map:")
+            .and_then(|name| split_pair(name, "-to-").ok())
+            .ok_or_else(|| ParseError::new(header, "a `source-to-target map:` header"))?;
+
+        let mut pieces = PiecewiseMap::new();
+        for line in ranges.lines() {
+            let range = Range::new(line)?;
+            pieces.insert(range.domain(), range.offset());
+        }
+
+        Ok(Self { from, to, pieces })
+    }
+
+    fn convert(&self, input: i64) -> i64 {
+        self.pieces.apply(input)
+    }
+
+    fn convert_range(&self, seed_ranges: &IntervalSet) -> IntervalSet {
+        self.pieces.apply_to_set(seed_ranges)
+    }
+}
+
+impl<'a> Almanac<'a> {
+    fn new(maps: Vec<Map<'a>>) -> anyhow::Result<Self> {
+        let mut pairs = HashSet::new();
+        for map in &maps {
+            if !pairs.insert((map.from, map.to)) {
+                bail!("there are two {}-to-{} maps", map.from, map.to);
+            }
+        }
+
+        let almanac = Almanac(maps);
+        if let Some(cycle) = almanac.find_cycle() {
+            bail!(
+                "the categories go round in a circle: {}",
+                cycle.join(" -> ")
+            );
+        }
+        Ok(almanac)
+    }
+
+    fn targets<'s>(&'s self, from: &'s str) -> impl Iterator<Item = &'s Map<'a>> {
+        self.0.iter().filter(move |map| map.from == from)
+    }
+
+    fn find_cycle(&self) -> Option<Vec<&str>> {
+        fn visit<'a>(
+            almanac: &'a Almanac,
+            category: &'a str,
+            path: &mut Vec<&'a str>,
+            done: &mut HashSet<&'a str>,
+        ) -> Option<Vec<&'a str>> {
+            if let Some(start) = path.iter().position(|c| *c == category) {
+                let mut cycle = path[start..].to_vec();
+                cycle.push(category);
+                return Some(cycle);
+            }
+            if !done.insert(category) {
+                return None;
+            }
+
+            path.push(category);
+            for map in almanac.targets(category) {
+                if let Some(cycle) = visit(almanac, map.to, path, done) {
+                    return Some(cycle);
+                }
+            }
+            path.pop();
+            None
+        }
+
+        let mut done = HashSet::new();
+        self.0
+            .iter()
+            .find_map(|map| visit(self, map.from, &mut Vec::new(), &mut done))
+    }
+
+    /// The maps to run through, in order, to get from one category to another.
+    pub fn chain<'s>(&'s self, from: &'s str, to: &'s str) -> anyhow::Result<Vec<&'s Map<'a>>> {
+        let mut came_by: HashMap<&str, &Map> = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+
+        while let Some(category) = queue.pop_front() {
+            if category == to {
+                let mut chain = Vec::new();
+                let mut current = to;
+                while current != from {
+                    let map = came_by[current];
+                    chain.push(map);
+                    current = map.from;
+                }
+                chain.reverse();
+                return Ok(chain);
+            }
+
+            for map in self.targets(category) {
+                if map.to != from && !came_by.contains_key(map.to) {
+                    came_by.insert(map.to, map);
+                    queue.push_back(map.to);
+                }
+            }
+        }
+
+        bail!("no chain of maps leads from {from} to {to}")
+    }
+}
+
+impl Seed {
+    fn get_path(&self, maps: &[&Map]) -> i64 {
+        let Seed(n) = self;
+        maps.iter()
+            .fold(*n, |last_path, map| map.convert(last_path))
+    }
+}
+
+fn parse_seed_ranges(seeds: &str) -> Result<IntervalSet, ParseError> {
+    let numbers = numbers::<i64>(labelled(seeds, "seeds")?)?;
+    if numbers.len() % 2 == 1 {
+        return Err(ParseError::new(seeds, "seeds in `start length` pairs"));
+    }
+    if numbers
+        .chunks(2)
+        .any(|range| range[0].checked_add(range[1]).is_none())
+    {
+        return Err(ParseError::new(
+            seeds,
+            "seed ranges that end before i64::MAX",
+        ));
+    }
+    Ok(numbers
+        .chunks(2)
+        .map(|range| Interval::with_len(range[0], range[1]))
+        .collect())
+}
+
+fn parse_seeds(seeds: &str) -> Result<Vec<Seed>, ParseError> {
+    let numbers = numbers(labelled(seeds, "seeds")?)?;
+    Ok(numbers.into_iter().map(Seed).collect())
+}
+
+pub fn parse_input(input: &str) -> anyhow::Result<(Vec<Seed>, Almanac<'_>, IntervalSet)> {
+    let sections = blocks(input);
+    let (seed_line, maps) = sections
+        .split_first()
+        .ok_or_else(|| anyhow!("the almanac is empty"))?;
+    let locate = |e: ParseError| e.locate(input);
+    let maps = maps
+        .iter()
+        .map(|map| Map::new(map).map_err(locate))
+        .collect::<Result<_, _>>()?;
+    let almanac = Almanac::new(maps)?;
+    let seeds = parse_seeds(seed_line).map_err(locate)?;
+    let seed_ranges = parse_seed_ranges(seed_line).map_err(locate)?;
+    Ok((seeds, almanac, seed_ranges))
+}
+
+/// Squashes a chain of maps into a single map, e.g. straight from seed to location.
+pub fn compose(maps: &[&Map]) -> PiecewiseMap {
+    maps.iter().fold(PiecewiseMap::new(), |composed, map| {
+        composed.then(&map.pieces)
+    })
+}
+
+/// All the seeds that end up at `location`, or `None` for `i64::MAX`, which no interval can
+/// hold.
+pub fn seeds_reaching(almanac: &PiecewiseMap, location: i64) -> Option<IntervalSet> {
+    let end = location.checked_add(1)?;
+    Some(almanac.preimage(&Interval::new(location, end).into()))
+}
+
+/// `None` when there are no seeds to plant.
+pub fn solve_part_one(seeds: &[Seed], maps: &[&Map]) -> Option<i64> {
+    seeds.iter().map(|s| s.get_path(maps)).min()
+}
+
+/// `None` when there are no seeds to plant.
+pub fn solve_part_two(seeds: IntervalSet, maps: &[&Map]) -> Option<i64> {
+    maps.iter()
+        .fold(seeds, |current, map| map.convert_range(&current))
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use aoc::{
+        alloc::{allocations, Counting},
+        input::{as_windows, normalize},
+        interval::{Interval, IntervalSet},
+        parse::blocks,
+    };
+    use proptest::{collection::vec, prelude::*};
+
+    use crate::{
+        compose, generate::generate, parse_input, seeds_reaching, solve_part_one, solve_part_two,
+        Map, Range,
+    };
+
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+
+    const SAMPLE: &str = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+
+    #[test]
+    fn composed_almanac_matches_map_by_map() {
+        let (seeds, almanac, seed_ranges) = parse_input(SAMPLE).unwrap();
+        let maps = almanac.chain("seed", "location").unwrap();
+        let almanac = compose(&maps);
+        for seed in 0..120 {
+            let stepped = maps.iter().fold(seed, |n, map| map.convert(n));
+            assert_eq!(almanac.apply(seed), stepped);
+        }
+        assert_eq!(seeds[0].get_path(&maps), 82);
+        assert_eq!(
+            almanac.apply_to_set(&seed_ranges).min(),
+            solve_part_two(seed_ranges, &maps)
+        );
+    }
+
+    #[test]
+    fn agrees_with_generated_inputs() {
+        for seed in 0..20 {
+            let (input, part_one, part_two) = generate(50, seed);
+            let (seeds, almanac, seed_ranges) = parse_input(&input).unwrap();
+            let maps = almanac.chain("seed", "location").unwrap();
+            assert_eq!(solve_part_one(&seeds, &maps), Some(part_one), "seed {seed}");
+            assert_eq!(
+                solve_part_two(seed_ranges, &maps),
+                Some(part_two),
+                "seed {seed}"
+            );
+        }
+    }
+
+    #[test]
+    fn line_endings_make_no_difference() {
+        let windows = normalize(&as_windows(SAMPLE)).into_owned();
+        let (seeds, almanac, seed_ranges) = parse_input(&windows).unwrap();
+        let maps = almanac.chain("seed", "location").unwrap();
+        assert_eq!(seeds.iter().map(|s| s.get_path(&maps)).min(), Some(35));
+        assert_eq!(solve_part_two(seed_ranges, &maps), Some(46));
+    }
+
+    #[test]
+    fn inverts_the_almanac() {
+        let (_, almanac, _) = parse_input(SAMPLE).unwrap();
+        let almanac = compose(&almanac.chain("seed", "location").unwrap());
+        let inverse = almanac.inverse().unwrap();
+        assert_eq!(inverse.apply(82), 79);
+        assert!(seeds_reaching(&almanac, 46).unwrap().contains(82));
+        assert!(seeds_reaching(&almanac, i64::MAX).is_none());
+    }
+
+    #[test]
+    fn follows_categories_in_any_order() {
+        let (_, almanac, _) = parse_input(SAMPLE).unwrap();
+        let names = |maps: Vec<&Map>| maps.iter().map(|m| m.to.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            names(almanac.chain("water", "temperature").unwrap()),
+            ["light", "temperature"]
+        );
+        assert!(almanac.chain("seed", "seed").unwrap().is_empty());
+        assert!(almanac.chain("location", "seed").is_err());
+
+        let mut sections: Vec<&str> = SAMPLE.split("\n\n").collect();
+        sections[1..].reverse();
+        let shuffled = sections.join("\n\n");
+        let (_, shuffled, _) = parse_input(&shuffled).unwrap();
+        let seeds = Interval::with_len(79, 14).into();
+        assert_eq!(
+            solve_part_two(seeds, &shuffled.chain("seed", "location").unwrap()),
+            Some(46)
+        );
+    }
+
+    #[test]
+    fn rejects_broken_category_graphs() {
+        let cyclic = "seeds: 1\n\na-to-b map:\n0 0 1\n\nb-to-c map:\n0 0 1\n\nc-to-a map:\n0 0 1";
+        let err = parse_input(cyclic).unwrap_err().to_string();
+        assert!(err.contains("a -> b -> c -> a"), "{err}");
+
+        let twice = "seeds: 1\n\na-to-b map:\n0 0 1\n\na-to-b map:\n0 0 1";
+        assert!(parse_input(twice).is_err());
+        assert!(parse_input("seeds: 1\n\nnonsense:\n0 0 1").is_err());
+    }
+
+    #[test]
+    fn says_where_the_almanac_is_broken() {
+        let err = parse_input("seeds: 1 2\n\na-to-b map:\n0 0 1\n0 x 1").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 5, column 3: expected a number, found \"x\""
+        );
+        let err = parse_input("seed: 1 2\n\na-to-b map:\n0 0 1").unwrap_err();
+        assert!(err.to_string().starts_with("line 1, column 1:"), "{err}");
+    }
+
+    #[test]
+    fn has_no_answer_without_seeds() {
+        let (seeds, almanac, seed_ranges) = parse_input("seeds:\n\na-to-b map:\n0 0 1").unwrap();
+        let maps = almanac.chain("a", "b").unwrap();
+        assert_eq!(solve_part_one(&seeds, &maps), None);
+        assert_eq!(solve_part_two(seed_ranges, &maps), None);
+    }
+
+    #[test]
+    fn rejects_ranges_that_overflow() {
+        let err = parse_input("seeds: 1 9223372036854775807").unwrap_err();
+        assert!(err.to_string().contains("end before i64::MAX"), "{err}");
+        assert!(Range::new("0 9223372036854775807 1").is_err());
+        assert!(Range::new("9223372036854775807 0 1").is_err());
+        assert!(Range::new("1 2").is_err());
+        assert!(Range::new("0 9223372036854775806 1").is_ok());
+    }
+
+    /// Converts one seed at a time, the slow and obvious way.
+    fn oracle(ranges: &[(i64, i64, i64)], seed: i64) -> i64 {
+        ranges
+            .iter()
+            .find(|(_, source, length)| *source <= seed && seed < source + length)
+            .map(|(dest, source, _)| seed - source + dest)
+            .unwrap_or(seed)
+    }
+
+    fn map_text(ranges: &[(i64, i64, i64)]) -> String {
+        let lines: Vec<String> = ranges
+            .iter()
+            .map(|(d, s, l)| format!("{d} {s} {l}"))
+            .collect();
+        format!("x-to-y map:\n{}", lines.join("\n"))
+    }
+
+    fn points(set: &IntervalSet) -> BTreeSet<i64> {
+        set.iter().flat_map(|i| i.start..i.end).collect()
+    }
+
+    #[test]
+    fn parsing_borrows_the_categories() {
+        let text: String = (0..200)
+            .map(|n| format!("c{n}-to-c{} map:\n{n} 0 1\n\n", n + 1))
+            .collect();
+        let sections = blocks(&text);
+        let (maps, borrowed) = allocations(|| {
+            sections
+                .iter()
+                .map(|map| Map::new(map).unwrap())
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(maps[199].to, "c200");
+        // A few allocations per map for its pieces, and none for its two categories
+        assert!(borrowed < 6 * 200, "{borrowed} allocations parsing");
+    }
+
+    proptest! {
+        #[test]
+        fn convert_range_matches_seed_by_seed(
+            almanac in vec(vec((0..200_i64, 0..200_i64, 0..40_i64), 0..6), 1..5),
+            seeds in vec((0..250_i64, 0..40_i64), 1..4),
+        ) {
+            let texts: Vec<String> = almanac.iter().map(|ranges| map_text(ranges)).collect();
+            let maps: Vec<Map> = texts.iter().map(|text| Map::new(text).unwrap()).collect();
+            let mut current: IntervalSet = seeds
+                .iter()
+                .map(|&(start, len)| Interval::with_len(start, len))
+                .collect();
+            let mut expected: BTreeSet<i64> = points(&current);
+
+            for (map, ranges) in maps.iter().zip(&almanac) {
+                current = map.convert_range(&current);
+                expected = expected.iter().map(|&seed| oracle(ranges, seed)).collect();
+
+                prop_assert!(current.iter().all(|i| !i.is_empty()));
+                prop_assert_eq!(points(&current), expected.clone());
+            }
+        }
+    }
+}
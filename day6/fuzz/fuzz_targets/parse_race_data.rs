@@ -0,0 +1,9 @@
+#![no_main]
+
+use day6::Kerning;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = day6::parse_race_data(input, Kerning::Respected);
+    let _ = day6::parse_race_data(input, Kerning::Ignored);
+});
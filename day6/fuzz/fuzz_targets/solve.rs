@@ -0,0 +1,12 @@
+#![no_main]
+
+use day6::Kerning;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    for kerning in [Kerning::Respected, Kerning::Ignored] {
+        if let Ok(races) = day6::parse_race_data(input, kerning) {
+            day6::solve(races);
+        }
+    }
+});
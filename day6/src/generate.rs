@@ -0,0 +1,77 @@
+//! Made-up race sheets of any size, with answers worked out by trying every hold in the short
+//! races and binary searching the long one.
+
+use aoc::rng::Rng;
+
+fn beats(hold: u64, duration: u64, record: u64) -> bool {
+    hold as u128 * (duration - hold) as u128 > record as u128
+}
+
+/// Holds below the peak only ever go further the longer they are, so the first winner can be
+/// binary searched for, and the rest mirror it.
+fn winners(duration: u64, record: u64) -> u64 {
+    let (mut lo, mut hi) = (0, duration / 2 + 1);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if beats(mid, duration, record) {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    match lo <= duration / 2 {
+        true => duration - 2 * lo + 1,
+        false => 0,
+    }
+}
+
+/// A sheet of `size` races, and the answers to both parts for it. Either answer is `None` when
+/// it doesn't fit in a `u64`, which for part two happens past four or five races, as the
+/// records run together into one enormous number.
+pub fn generate(size: usize, seed: u64) -> (String, Option<u64>, Option<u64>) {
+    let mut rng = Rng::new(seed);
+    let races: Vec<(u64, u64)> = (0..size)
+        .map(|_| {
+            let duration = rng.range(2..100);
+            // Somewhere below the furthest anyone can go, so every race can be won
+            let best = (duration / 2) * (duration - duration / 2);
+            (duration, rng.range(0..best))
+        })
+        .collect();
+
+    let part_one = races
+        .iter()
+        .try_fold(1_u64, |product, &(duration, record)| {
+            let count = (0..=duration)
+                .filter(|&hold| beats(hold, duration, record))
+                .count();
+            product.checked_mul(count as u64)
+        });
+
+    let concat = |column: fn(&(u64, u64)) -> u64| {
+        let digits: String = races.iter().map(|race| column(race).to_string()).collect();
+        digits.parse::<u64>().ok()
+    };
+    let part_two = concat(|race| race.0)
+        .zip(concat(|race| race.1))
+        .map(|(duration, record)| winners(duration, record));
+
+    let widths: Vec<usize> = races
+        .iter()
+        .map(|(duration, record)| duration.to_string().len().max(record.to_string().len()))
+        .collect();
+    let row = |label: &str, column: fn(&(u64, u64)) -> u64| {
+        let mut line = format!("{label:<9}");
+        for (race, width) in races.iter().zip(&widths) {
+            line.push_str(&format!("  {:>width$}", column(race)));
+        }
+        line
+    };
+    let text = format!(
+        "{}\n{}",
+        row("Time:", |race| race.0),
+        row("Distance:", |race| race.1)
+    );
+
+    (text, part_one, part_two)
+}
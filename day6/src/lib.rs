@@ -0,0 +1,408 @@
+use std::ops::RangeInclusive;
+
+use aoc::{
+    math::isqrt,
+    parse::{labelled, number, numbers, ParseError},
+};
+
+use motion::{Capped, Linear, Motion, Quadratic};
+
+pub mod generate;
+pub mod motion {
+    /// How far a boat gets in a race, given how long the button was held for.
+    ///
+    /// Models are expected to rise to a single peak and fall away again (or stay flat), which
+    /// is what lets [`crate::winning_holds`] binary search instead of trying every hold.
+    pub trait Motion {
+        fn distance(&self, hold: u64, duration: u64) -> u128;
+    }
+
+    /// The puzzle's boat: every millisecond of holding adds a millimetre per millisecond.
+    pub struct Linear;
+
+    /// A boat whose speed grows with the square of the hold time.
+    pub struct Quadratic;
+
+    /// A linear boat that can't go any faster than `max_speed`.
+    pub struct Capped {
+        pub max_speed: u64,
+    }
+
+    impl Motion for Linear {
+        fn distance(&self, hold: u64, duration: u64) -> u128 {
+            hold as u128 * (duration - hold) as u128
+        }
+    }
+
+    impl Motion for Quadratic {
+        fn distance(&self, hold: u64, duration: u64) -> u128 {
+            let speed = hold as u128 * hold as u128;
+            speed.saturating_mul((duration - hold) as u128)
+        }
+    }
+
+    impl Motion for Capped {
+        fn distance(&self, hold: u64, duration: u64) -> u128 {
+            hold.min(self.max_speed) as u128 * (duration - hold) as u128
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Race {
+    duration: u64,
+    record: u64,
+}
+
+/// How to read the columns of the sheet. Part two reveals the spaces between the numbers were
+/// just bad kerning, and the whole sheet is really one long race.
+#[derive(Debug, Clone, Copy)]
+pub enum Kerning {
+    Respected,
+    Ignored,
+}
+
+impl Race {
+    fn distance(&self, hold: u64) -> u128 {
+        Linear.distance(hold, self.duration)
+    }
+
+    fn beats_record(&self, hold: u64) -> bool {
+        self.distance(hold) > self.record as u128
+    }
+
+    /// Counts the hold times that beat the record without trying each one.
+    ///
+    /// Holding for `h` goes `h * (duration - h)`, so the winners are the `h` strictly between
+    /// the roots of `h^2 - duration * h + record = 0`. The integer square root only gets us
+    /// within a step of the first root, so we nudge from there until we land on the first
+    /// winning hold. A hold that exactly matches the record doesn't count.
+    fn count_winning_holds(&self) -> u64 {
+        let duration = self.duration as u128;
+        let discriminant = match (duration * duration).checked_sub(4 * self.record as u128) {
+            Some(d) => d,
+            None => return 0,
+        };
+
+        let mut first = ((duration - isqrt(discriminant)) / 2) as u64;
+        while first > 0 && self.beats_record(first - 1) {
+            first -= 1;
+        }
+        while first <= self.duration / 2 && !self.beats_record(first) {
+            first += 1;
+        }
+        if first > self.duration / 2 {
+            return 0;
+        }
+
+        // The parabola is symmetric, so the last winner mirrors the first
+        self.duration - 2 * first + 1
+    }
+
+    #[cfg(test)]
+    fn count_winning_holds_slowly(&self) -> u64 {
+        (1..=self.duration)
+            .filter(|hold| self.beats_record(*hold))
+            .count() as u64
+    }
+}
+
+/// The hold times that beat the record under any motion model, as one unbroken stretch.
+pub fn winning_holds(race: &Race, motion: &dyn Motion) -> Option<RangeInclusive<u64>> {
+    let distance = |hold| motion.distance(hold, race.duration);
+    let record = race.record as u128;
+
+    // Smallest hold where holding any longer stops helping
+    let (mut lo, mut hi) = (0, race.duration);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if distance(mid) >= distance(mid + 1) {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    let peak = lo;
+    if distance(peak) <= record {
+        return None;
+    }
+
+    let (mut lo, mut hi) = (0, peak);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if distance(mid) > record {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    let first = lo;
+
+    let (mut lo, mut hi) = (peak, race.duration);
+    while lo < hi {
+        let mid = hi - (hi - lo) / 2;
+        if distance(mid) > record {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+
+    Some(first..=lo)
+}
+
+/// Distance for every hold time as `hold,distance` lines, ready to plot.
+pub fn plot(race: &Race, motion: &dyn Motion) -> String {
+    (0..=race.duration)
+        .map(|hold| format!("{hold},{}\n", motion.distance(hold, race.duration)))
+        .collect()
+}
+
+/// Every motion model by the name `--plot` knows it by, with the capped boat held to
+/// `max_speed`.
+pub fn fleet(max_speed: u64) -> Vec<(&'static str, Box<dyn Motion>)> {
+    vec![
+        ("linear", Box::new(Linear)),
+        ("quadratic", Box::new(Quadratic)),
+        ("capped", Box::new(Capped { max_speed })),
+    ]
+}
+
+/// `None` when the product won't fit in a `u64`.
+pub fn solve(races: Vec<Race>) -> Option<u64> {
+    races.iter().try_fold(1_u64, |product, race| {
+        product.checked_mul(race.count_winning_holds())
+    })
+}
+
+/// A column read as one number, with the spaces between its digits taken out.
+fn unkerned(column: &str) -> Result<u64, ParseError> {
+    let digits: String = column.split_whitespace().collect();
+    number(&digits).map_err(|e| ParseError::new(column, e.expected))
+}
+
+fn parse_sheet(input: &str, kerning: Kerning) -> Result<Vec<Race>, ParseError> {
+    let mut lines = input.lines();
+    let end = &input[input.len()..];
+    let times = labelled(lines.next().unwrap_or(end), "Time")?;
+    let records = labelled(lines.next().unwrap_or(end), "Distance")?;
+    let durations: Vec<u64> = numbers(times)?;
+    let bests: Vec<u64> = numbers(records)?;
+    if durations.len() != bests.len() {
+        let expected = format!("{} records, one for each time", durations.len());
+        return Err(ParseError::new(records, expected));
+    }
+
+    match kerning {
+        Kerning::Respected => Ok(durations
+            .into_iter()
+            .zip(bests)
+            .map(|(duration, record)| Race { duration, record })
+            .collect()),
+        Kerning::Ignored => Ok(vec![Race {
+            duration: unkerned(times)?,
+            record: unkerned(records)?,
+        }]),
+    }
+}
+
+/// Errors say where in `input` they are.
+pub fn parse_race_data(input: &str, kerning: Kerning) -> Result<Vec<Race>, ParseError> {
+    parse_sheet(input, kerning).map_err(|e| e.locate(input))
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc::input::{as_windows, normalize};
+    use proptest::prelude::*;
+
+    use crate::{
+        fleet,
+        generate::generate,
+        motion::{Capped, Linear, Motion, Quadratic},
+        parse_race_data, solve, winning_holds, Kerning, Race,
+    };
+
+    const SAMPLE: &str = "Time:      7  15   30\nDistance:  9  40  200\n";
+
+    #[test]
+    fn parses_both_parts_from_one_sheet() {
+        let races = parse_race_data(SAMPLE, Kerning::Respected).unwrap();
+        assert_eq!(races.len(), 3);
+        assert_eq!(solve(races), Some(288));
+
+        let races = parse_race_data(SAMPLE, Kerning::Ignored).unwrap();
+        assert_eq!((races[0].duration, races[0].record), (71530, 940200));
+        assert_eq!(solve(races), Some(71503));
+    }
+
+    #[test]
+    fn agrees_with_generated_inputs() {
+        for seed in 0..50 {
+            let size = 1 + seed as usize % 6;
+            let (input, part_one, part_two) = generate(size, seed);
+            let races = parse_race_data(&input, Kerning::Respected).unwrap();
+            assert_eq!(solve(races), part_one, "seed {seed}");
+            match (parse_race_data(&input, Kerning::Ignored), part_two) {
+                (Ok(races), Some(part_two)) => {
+                    assert_eq!(solve(races), Some(part_two), "seed {seed}")
+                }
+                (Err(_), None) => {}
+                (races, part_two) => panic!("seed {seed}: {races:?} against {part_two:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn line_endings_make_no_difference() {
+        let windows = normalize(&as_windows(SAMPLE)).into_owned();
+        let tabbed = normalize(&SAMPLE.replace("  ", "\t")).into_owned();
+        for input in [&windows, &tabbed] {
+            assert_eq!(
+                solve(parse_race_data(input, Kerning::Respected).unwrap()),
+                Some(288)
+            );
+            assert_eq!(
+                solve(parse_race_data(input, Kerning::Ignored).unwrap()),
+                Some(71503)
+            );
+        }
+    }
+
+    #[test]
+    fn reports_bad_sheets() {
+        let error = |input| {
+            parse_race_data(input, Kerning::Ignored)
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            error("Time: 7 1x\nDistance: 9 40"),
+            "line 1, column 9: expected a number, found \"1x\""
+        );
+        assert_eq!(
+            error("Time: 7 15"),
+            "line 1, column 11: expected a \"Distance\" section, found \"\""
+        );
+        assert_eq!(
+            error("Time: 7 15\nDistance: 9"),
+            "line 2, column 11: expected 2 records, one for each time, found \"9\""
+        );
+        assert_eq!(
+            error("Time: 99999 99999 99999 99999 99999\nDistance: 1 1 1 1 1"),
+            "line 1, column 7: expected a number small enough to hold, \
+             found \"99999 99999 99999 99999 99999\""
+        );
+    }
+
+    #[test]
+    fn overflows_instead_of_wrapping() {
+        let races = parse_race_data(
+            "Time: 99999999999 99999999999\nDistance: 0 0",
+            Kerning::Respected,
+        );
+        assert_eq!(solve(races.unwrap()), None);
+    }
+
+    #[test]
+    fn counts_the_sample_races() {
+        let counts: Vec<u64> = [(7, 9), (15, 40), (30, 200), (71530, 940200)]
+            .into_iter()
+            .map(|(duration, record)| Race { duration, record }.count_winning_holds())
+            .collect();
+        assert_eq!(counts, [4, 8, 9, 71503]);
+    }
+
+    #[test]
+    fn matching_the_record_does_not_win() {
+        // Holding for 2 or 4 goes exactly 8, only holding for 3 goes further
+        assert_eq!(
+            Race {
+                duration: 6,
+                record: 8
+            }
+            .count_winning_holds(),
+            1
+        );
+        assert_eq!(
+            Race {
+                duration: 6,
+                record: 9
+            }
+            .count_winning_holds(),
+            0
+        );
+        assert_eq!(
+            Race {
+                duration: 0,
+                record: 0
+            }
+            .count_winning_holds(),
+            0
+        );
+    }
+
+    #[test]
+    fn handles_enormous_races() {
+        let race = Race {
+            duration: u64::MAX,
+            record: 0,
+        };
+        assert_eq!(race.count_winning_holds(), u64::MAX - 1);
+    }
+
+    #[test]
+    fn finds_winning_intervals() {
+        let race = Race {
+            duration: 7,
+            record: 9,
+        };
+        assert_eq!(winning_holds(&race, &Linear), Some(2..=5));
+        // 4 * 4 * 3 = 48 is the best the quadratic boat can do
+        assert_eq!(winning_holds(&race, &Quadratic), Some(2..=6));
+        assert_eq!(winning_holds(&race, &Capped { max_speed: 2 }), Some(2..=2));
+        assert_eq!(winning_holds(&race, &Capped { max_speed: 1 }), None);
+    }
+
+    #[test]
+    fn caps_the_fleet_at_the_given_speed() {
+        let race = Race {
+            duration: 7,
+            record: 9,
+        };
+        let fleet = fleet(2);
+        let (_, capped) = fleet.iter().find(|(name, _)| *name == "capped").unwrap();
+        assert_eq!(winning_holds(&race, capped.as_ref()), Some(2..=2));
+    }
+
+    fn brute_force(race: &Race, motion: &dyn Motion) -> Option<(u64, u64)> {
+        let winners: Vec<u64> = (0..=race.duration)
+            .filter(|hold| motion.distance(*hold, race.duration) > race.record as u128)
+            .collect();
+        Some((*winners.first()?, *winners.last()?))
+    }
+
+    proptest! {
+        #[test]
+        fn winning_holds_match_brute_force(
+            duration in 0..300_u64,
+            record in 0..100_000_u64,
+            max_speed in 0..300_u64,
+        ) {
+            let race = Race { duration, record };
+            let motions: [&dyn Motion; 3] = [&Linear, &Quadratic, &Capped { max_speed }];
+            for motion in motions {
+                let found = winning_holds(&race, motion).map(|r| (*r.start(), *r.end()));
+                prop_assert_eq!(found, brute_force(&race, motion));
+            }
+            let linear = winning_holds(&race, &Linear).map_or(0, |r| r.end() - r.start() + 1);
+            prop_assert_eq!(linear, race.count_winning_holds());
+        }
+
+        #[test]
+        fn closed_form_matches_brute_force(duration in 0..2_000_u64, record in 0..1_000_000_u64) {
+            let race = Race { duration, record };
+            prop_assert_eq!(race.count_winning_holds(), race.count_winning_holds_slowly());
+        }
+    }
+}
@@ -1,46 +1,50 @@
-#[derive(Debug)]
-struct Race {
-    duration: usize,
-    record: usize,
-}
-
-impl Race {
-    fn new(duration: &str, record: &str) -> Self {
-        let duration = duration.parse().unwrap_or(0);
-        let record = record.parse().unwrap_or(0);
-        Race { duration, record }
-    }
-}
+use std::error::Error;
 
-fn solve(races: Vec<Race>) -> usize {
-    races
-        .iter()
-        .map(|race| {
-            let mut count = 0;
-            for i in 1..=race.duration {
-                if (race.duration - i) * i > race.record {
-                    count += 1
-                }
-            }
-            count
-        })
-        .product()
-}
+use aoc::{
+    cli::size_and_seed,
+    input::normalize,
+    parse::{number, ParseError},
+};
+use day6::{fleet, generate, parse_race_data, plot, solve, winning_holds, Kerning};
 
-fn parse_race_data(input: &str) -> Vec<Race> {
-    let (times, records) = input.split_once("\n").unwrap_or(("", ""));
-    let times = times.split_once(":").unwrap_or(("", "")).1;
-    let records = records.split_once(":").unwrap_or(("", "")).1;
-    times
-        .split_ascii_whitespace()
-        .zip(records.split_ascii_whitespace())
-        .map(|(time, distance)| Race::new(time, distance))
-        .collect()
-}
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = std::env::args().collect();
+    if let Some((size, seed)) = size_and_seed(&args, "--generate")? {
+        let (input, part_one, part_two) = generate::generate(size, seed);
+        println!("{input}");
+        eprintln!("part_one = {part_one:?}, part_two = {part_two:?}");
+        return Ok(());
+    }
 
-fn main() {
-    let part_one = solve(parse_race_data(include_str!("./input.txt")));
-    let part_two = solve(parse_race_data(include_str!("./input2.txt")));
+    let input = &*normalize(include_str!("./input.txt"));
+    let part_one = solve(parse_race_data(input, Kerning::Respected)?);
+    let part_two = solve(parse_race_data(input, Kerning::Ignored)?);
     dbg!(part_one);
     dbg!(part_two);
+
+    let max_speed = match args.windows(2).find(|w| w[0] == "--max-speed") {
+        Some(w) => number(&w[1])?,
+        None => 10,
+    };
+    if args.iter().any(|arg| arg == "--fleet") {
+        for race in parse_race_data(input, Kerning::Respected)? {
+            for (name, motion) in fleet(max_speed) {
+                println!(
+                    "{race:?} {name}: {:?}",
+                    winning_holds(&race, motion.as_ref())
+                );
+            }
+        }
+    }
+    if let Some(name) = args.windows(2).find(|w| w[0] == "--plot").map(|w| &w[1]) {
+        let (_, motion) = fleet(max_speed)
+            .into_iter()
+            .find(|(n, _)| n == name)
+            .ok_or_else(|| ParseError::new(name, "linear, quadratic or capped"))?;
+        for race in parse_race_data(input, Kerning::Respected)? {
+            println!("# {race:?}");
+            print!("{}", plot(&race, motion.as_ref()));
+        }
+    }
+    Ok(())
 }
@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|line: &str| {
+    if let Err(err) = line.parse::<day7::Hand>() {
+        assert!(err.location.is_some(), "{err:?}");
+    }
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use day7::RuleSet;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(hands) = day7::parse_hands(input) {
+        let _ = day7::solve(&hands, &RuleSet::standard());
+        let _ = day7::solve(&hands, &RuleSet::jokers_wild());
+    }
+});
@@ -0,0 +1,93 @@
+//! Made-up sets of Camel Cards hands of any size, with answers worked out by ranking every
+//! hand from scratch, trying every card in place of the jokers.
+
+use std::collections::HashSet;
+
+use aoc::rng::Rng;
+
+const STANDARD: &str = "23456789TJQKA";
+const JOKERS_LOW: &str = "J23456789TQKA";
+
+/// How a hand's cards group, biggest first, one shape per category.
+const SHAPES: [&[usize]; 7] = [
+    &[5],
+    &[4, 1],
+    &[3, 2],
+    &[3, 1, 1],
+    &[2, 2, 1],
+    &[2, 1, 1, 1],
+    &[1, 1, 1, 1, 1],
+];
+
+/// Higher is stronger: five of a kind is 6 and high card is 0.
+fn category(cards: &str) -> usize {
+    let mut counts: Vec<usize> = STANDARD
+        .chars()
+        .map(|card| cards.matches(card).count())
+        .filter(|&n| n > 0)
+        .collect();
+    counts.sort_unstable_by(|a, b| b.cmp(a));
+    SHAPES.len() - 1 - SHAPES.iter().position(|shape| *shape == counts).unwrap()
+}
+
+fn winnings(hands: &[(String, usize)], key: impl Fn(&str) -> (usize, Vec<usize>)) -> usize {
+    let mut ranked: Vec<_> = hands.iter().map(|(cards, bid)| (key(cards), bid)).collect();
+    ranked.sort_unstable();
+    ranked
+        .iter()
+        .enumerate()
+        .map(|(i, (_, bid))| (i + 1) * *bid)
+        .sum()
+}
+
+fn values(cards: &str, order: &str) -> Vec<usize> {
+    cards
+        .chars()
+        .map(|card| order.find(card).unwrap())
+        .collect()
+}
+
+/// `size` hands, none of them the same, and the answers to both parts for them.
+pub fn generate(size: usize, seed: u64) -> (String, usize, usize) {
+    assert!(
+        size <= 13_usize.pow(5),
+        "there aren't {size} different hands"
+    );
+    let mut rng = Rng::new(seed);
+    let mut seen = HashSet::new();
+    let mut hands = Vec::with_capacity(size);
+
+    // Picking a shape first gives every category a fair showing, where picking five cards
+    // at random would be mostly pairs and high cards
+    while hands.len() < size {
+        let mut cards: Vec<char> = STANDARD.chars().collect();
+        rng.shuffle(&mut cards);
+        let shape = rng.pick(&SHAPES);
+        let mut hand: Vec<char> = shape
+            .iter()
+            .zip(&cards)
+            .flat_map(|(&n, &card)| std::iter::repeat_n(card, n))
+            .collect();
+        rng.shuffle(&mut hand);
+        let hand: String = hand.into_iter().collect();
+        if seen.insert(hand.clone()) {
+            hands.push((hand, 1 + rng.below(1000)));
+        }
+    }
+
+    let part_one = winnings(&hands, |cards| (category(cards), values(cards, STANDARD)));
+    let part_two = winnings(&hands, |cards| {
+        let best = STANDARD
+            .chars()
+            .map(|card| category(&cards.replace('J', &card.to_string())))
+            .max()
+            .unwrap();
+        (best, values(cards, JOKERS_LOW))
+    });
+
+    let lines: Vec<String> = hands
+        .iter()
+        .map(|(cards, bid)| format!("{cards} {bid}"))
+        .collect();
+    (lines.join("\n"), part_one, part_two)
+}
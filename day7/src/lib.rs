@@ -0,0 +1,862 @@
+use anyhow::{anyhow, Result};
+use aoc::parse::ParseError;
+
+pub use hand::{Category, Classifier, Hand, Rank, RuleSet, Runs, SortKey, Standard};
+
+pub mod generate;
+
+mod hand {
+    use std::{rc::Rc, str::FromStr};
+
+    use aoc::{
+        counter::Counter,
+        parse::{number, split_pair, ParseError},
+    };
+
+    use anyhow::bail;
+
+    const STANDARD_ORDER: &str = "23456789TJQKA";
+
+    #[derive(Debug, Clone)]
+    pub struct Hand {
+        cards: Vec<Card>,
+        pub bid: usize,
+        hand_hash: Counter<Card>,
+    }
+
+    /// Everything needed to put hands in order, worked out once up front: the category of
+    /// hand first, then the card values left to right to break ties.
+    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct SortKey {
+        category: Category,
+        values: Vec<usize>,
+    }
+
+    /// A kind of hand, like a full house. Stronger categories beat weaker ones whatever the
+    /// cards are. The standard categories go up in tens to leave room for extra ones.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct Category {
+        pub strength: u8,
+        pub name: &'static str,
+    }
+
+    /// Decides which category a hand falls into.
+    ///
+    /// `groups` is how many of each card the hand has, biggest first, with any wild cards
+    /// already added to the biggest group.
+    pub trait Classifier: std::fmt::Debug {
+        fn classify(&self, cards: &[char], groups: &[usize]) -> Category;
+    }
+
+    /// Camel Cards categories, from high card up to five of a kind.
+    #[derive(Debug)]
+    pub struct Standard;
+
+    impl Classifier for Standard {
+        fn classify(&self, _cards: &[char], groups: &[usize]) -> Category {
+            Rank::from_groups(groups).category()
+        }
+    }
+
+    /// The standard categories plus a run: every card different and next to each other in
+    /// `order`, which beats three of a kind but not a full house.
+    #[derive(Debug)]
+    pub struct Runs {
+        order: Vec<char>,
+    }
+
+    impl Runs {
+        pub fn new(order: &str) -> Self {
+            Runs {
+                order: order.chars().collect(),
+            }
+        }
+    }
+
+    impl Classifier for Runs {
+        fn classify(&self, cards: &[char], groups: &[usize]) -> Category {
+            let standard = Standard.classify(cards, groups);
+            let mut positions: Vec<Option<usize>> = cards
+                .iter()
+                .map(|card| self.order.iter().position(|c| c == card))
+                .collect();
+            positions.sort_unstable();
+            let is_run = cards.len() > 1
+                && positions
+                    .windows(2)
+                    .all(|w| matches!((w[0], w[1]), (Some(a), Some(b)) if b == a + 1));
+            if is_run && standard.strength < 35 {
+                return Category {
+                    strength: 35,
+                    name: "run",
+                };
+            }
+            standard
+        }
+    }
+
+    /// How hands get ranked: which cards are in play, weakest first, which one (if any) is
+    /// wild, how many cards make a hand, and what categories a hand can fall into. A wild
+    /// card is always the weakest card.
+    #[derive(Debug, Clone)]
+    pub struct RuleSet {
+        wild: Option<Card>,
+        order: Vec<Card>,
+        hand_size: usize,
+        classifier: Rc<dyn Classifier>,
+    }
+
+    impl RuleSet {
+        pub fn standard() -> Self {
+            RuleSet {
+                wild: None,
+                order: STANDARD_ORDER.chars().map(Card).collect(),
+                hand_size: 5,
+                classifier: Rc::new(Standard),
+            }
+        }
+
+        pub fn jokers_wild() -> Self {
+            RuleSet::standard()
+                .with_wild('J')
+                .expect("J is a standard card")
+        }
+
+        pub fn with_wild(mut self, card: char) -> anyhow::Result<Self> {
+            if !self.order.contains(&Card(card)) {
+                bail!("{card} can't be wild, it isn't one of the cards");
+            }
+            self.wild = Some(Card(card));
+            Ok(self)
+        }
+
+        /// Swaps in a new alphabet of cards, weakest first. Each card can only show up once.
+        pub fn with_order(mut self, order: &str) -> anyhow::Result<Self> {
+            let mut cards: Vec<char> = order.chars().collect();
+            cards.sort_unstable();
+            cards.dedup();
+            if cards.len() != order.chars().count() || cards.is_empty() {
+                bail!("{order:?} needs at least one card and no card twice");
+            }
+            if cards.iter().any(|c| c.is_whitespace()) {
+                bail!("{order:?} can't use whitespace as a card");
+            }
+            self.order = order.chars().map(Card).collect();
+            if self.wild.is_some_and(|wild| !self.order.contains(&wild)) {
+                self.wild = None;
+            }
+            Ok(self)
+        }
+
+        pub fn with_hand_size(mut self, hand_size: usize) -> Self {
+            self.hand_size = hand_size;
+            self
+        }
+
+        pub fn with_classifier(mut self, classifier: impl Classifier + 'static) -> Self {
+            self.classifier = Rc::new(classifier);
+            self
+        }
+
+        /// Makes sure a hand can be played under these rules at all.
+        pub fn check(&self, hand: &Hand) -> anyhow::Result<()> {
+            if hand.cards.len() != self.hand_size {
+                bail!(
+                    "hands have {} cards, but one has {}",
+                    self.hand_size,
+                    hand.cards.len()
+                );
+            }
+            if let Some(Card(card)) = hand.cards.iter().find(|c| !self.order.contains(c)) {
+                bail!("{card} isn't one of the cards in play");
+            }
+            Ok(())
+        }
+
+        fn value(&self, card: &Card) -> usize {
+            if self.wild.as_ref() == Some(card) {
+                return 0;
+            }
+            self.order
+                .iter()
+                .position(|c| c == card)
+                .map_or(0, |i| i + 1)
+        }
+
+        /// How many of each card the hand has, biggest first. Wild cards always do best by
+        /// joining the biggest group of real cards.
+        fn groups(&self, counts: &Counter<Card>) -> Vec<usize> {
+            let mut real = counts.clone();
+            let wilds = self.wild.map_or(0, |wild| real.remove(&wild));
+            let mut groups = real.signature();
+            match groups.first_mut() {
+                Some(biggest) => *biggest += wilds,
+                None => groups.push(wilds),
+            }
+            groups
+        }
+    }
+
+    impl Hand {
+        pub fn cards(&self) -> String {
+            self.cards.iter().map(|Card(c)| c).collect()
+        }
+
+        /// The hand with its wild cards swapped for the card that makes the best hand, or
+        /// `None` if there's nothing wild in it. Wild cards copy the most common real card,
+        /// the strongest one on a tie, and the strongest card of all when every card is wild.
+        pub fn substitution(&self, rules: &RuleSet) -> Option<String> {
+            let wild = rules.wild?;
+            if !self.hand_hash.contains(&wild) {
+                return None;
+            }
+            let Card(best) = self
+                .hand_hash
+                .iter()
+                .filter(|(card, _)| **card != wild)
+                .max_by_key(|(card, n)| (**n, rules.value(card)))
+                .map(|(card, _)| *card)
+                .or_else(|| rules.order.last().copied())?;
+            Some(
+                self.cards
+                    .iter()
+                    .map(|card| if *card == wild { best } else { card.0 })
+                    .collect(),
+            )
+        }
+
+        /// Where the hand sorts under `rules`: weaker hands have smaller keys.
+        pub fn sort_key(&self, rules: &RuleSet) -> SortKey {
+            SortKey {
+                category: self.category(rules),
+                values: self.cards.iter().map(|card| rules.value(card)).collect(),
+            }
+        }
+
+        pub fn category(&self, rules: &RuleSet) -> Category {
+            let cards: Vec<char> = self.cards.iter().map(|Card(c)| *c).collect();
+            rules
+                .classifier
+                .classify(&cards, &rules.groups(&self.hand_hash))
+        }
+    }
+
+    /// Hands are the same hand whatever rules they happen to be ranked under.
+    impl PartialEq for Hand {
+        fn eq(&self, other: &Self) -> bool {
+            self.cards == other.cards && self.bid == other.bid
+        }
+    }
+
+    impl Eq for Hand {}
+
+    #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord)]
+    pub enum Rank {
+        FiveKind,
+        FourKind,
+        FullHouse,
+        ThreeKind,
+        TwoPair,
+        Pair,
+        HighCard,
+    }
+
+    impl Rank {
+        /// Ranks a hand from the sizes of its groups of matching cards, biggest first. Hands
+        /// bigger than five cards get the rank of the best five cards in them.
+        fn from_groups(groups: &[usize]) -> Rank {
+            match groups {
+                [5..=usize::MAX, ..] => Rank::FiveKind,
+                [4, ..] => Rank::FourKind,
+                [3, 2..=usize::MAX, ..] => Rank::FullHouse,
+                [3, ..] => Rank::ThreeKind,
+                [2, 2, ..] => Rank::TwoPair,
+                [2, ..] => Rank::Pair,
+                _ => Rank::HighCard,
+            }
+        }
+
+        pub fn category(self) -> Category {
+            let (strength, name) = match self {
+                Rank::FiveKind => (60, "five of a kind"),
+                Rank::FourKind => (50, "four of a kind"),
+                Rank::FullHouse => (40, "full house"),
+                Rank::ThreeKind => (30, "three of a kind"),
+                Rank::TwoPair => (20, "two pair"),
+                Rank::Pair => (10, "one pair"),
+                Rank::HighCard => (0, "high card"),
+            };
+            Category { strength, name }
+        }
+    }
+
+    impl FromStr for Hand {
+        type Err = ParseError;
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            Hand::parse(s).map_err(|e| e.locate(s))
+        }
+    }
+
+    impl Hand {
+        fn parse(s: &str) -> Result<Self, ParseError> {
+            let (cards, bid) = split_pair(s, " ")?;
+            if cards.is_empty() {
+                return Err(ParseError::new(cards, "at least one card"));
+            }
+            let cards = cards
+                .char_indices()
+                .map(|(i, c)| cards[i..i + c.len_utf8()].parse())
+                .collect::<Result<Vec<Card>, _>>()?;
+
+            let bid = number(bid)?;
+            Ok(Hand {
+                hand_hash: cards.iter().copied().collect(),
+                cards,
+                bid,
+            })
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    struct Card(char);
+
+    impl FromStr for Card {
+        type Err = ParseError;
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if !c.is_whitespace() => Ok(Self(c)),
+                _ => Err(ParseError::new(s, "a single card")),
+            }
+        }
+    }
+}
+
+/// Puts the hands in order from weakest to strongest under `rules`, working out each hand's
+/// key just once.
+pub fn sort_hands(hands: &mut [Hand], rules: &RuleSet) {
+    hands.sort_by_cached_key(|hand| (hand.sort_key(rules), hand.bid));
+}
+
+pub fn parse_hands(input: &str) -> Result<Vec<Hand>> {
+    let mut hands = Vec::new();
+    for line in input.lines() {
+        hands.push(line.parse().map_err(|e: ParseError| e.locate(input))?)
+    }
+    Ok(hands)
+}
+
+/// Where a hand finished and what it won, so a total can be checked line by line.
+#[derive(Debug, PartialEq)]
+pub struct Standing {
+    position: usize,
+    cards: String,
+    category: &'static str,
+    substitution: Option<String>,
+    bid: usize,
+    winnings: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Format {
+    Text,
+    Csv,
+    Json,
+}
+
+impl std::str::FromStr for Format {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            _ => Err(anyhow::anyhow!("unknown report format {s:?}")),
+        }
+    }
+}
+
+pub fn standings(hands: &[Hand], rules: &RuleSet) -> Result<Vec<Standing>> {
+    for hand in hands {
+        rules.check(hand)?;
+    }
+    let mut hands = hands.to_vec();
+    sort_hands(&mut hands, rules);
+    hands
+        .iter()
+        .enumerate()
+        .map(|(i, hand)| {
+            let winnings = (i + 1)
+                .checked_mul(hand.bid)
+                .ok_or_else(|| anyhow!("{} wins more than a usize can hold", hand.cards()))?;
+            Ok(Standing {
+                position: i + 1,
+                cards: hand.cards(),
+                category: hand.category(rules).name,
+                substitution: hand.substitution(rules),
+                bid: hand.bid,
+                winnings,
+            })
+        })
+        .collect()
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+/// A CSV field, quoted only when it holds a comma, a quote or a line break. Custom card
+/// alphabets can hold any of them.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+pub fn report(standings: &[Standing], format: Format) -> String {
+    let mut out = String::new();
+    match format {
+        Format::Text => {
+            out.push_str(&format!(
+                "{:>6}  {:<8}{:<17}{:<8}{:>6}{:>10}\n",
+                "rank", "hand", "category", "as", "bid", "winnings"
+            ));
+            for s in standings {
+                out.push_str(&format!(
+                    "{:>6}  {:<8}{:<17}{:<8}{:>6}{:>10}\n",
+                    s.position,
+                    s.cards,
+                    s.category,
+                    s.substitution.as_deref().unwrap_or("-"),
+                    s.bid,
+                    s.winnings
+                ));
+            }
+            let total: usize = standings.iter().map(|s| s.winnings).sum();
+            out.push_str(&format!("total winnings: {total}\n"));
+        }
+        Format::Csv => {
+            out.push_str("position,hand,category,substitution,bid,winnings\n");
+            for s in standings {
+                out.push_str(&format!(
+                    "{},{},{},{},{},{}\n",
+                    s.position,
+                    csv_field(&s.cards),
+                    csv_field(s.category),
+                    csv_field(s.substitution.as_deref().unwrap_or("")),
+                    s.bid,
+                    s.winnings
+                ));
+            }
+        }
+        Format::Json => {
+            let rows: Vec<String> = standings
+                .iter()
+                .map(|s| {
+                    format!(
+                        "{{\"position\":{},\"hand\":{},\"category\":{},\"substitution\":{},\"bid\":{},\"winnings\":{}}}",
+                        s.position,
+                        json_string(&s.cards),
+                        json_string(s.category),
+                        s.substitution.as_deref().map_or("null".to_string(), json_string),
+                        s.bid,
+                        s.winnings
+                    )
+                })
+                .collect();
+            out.push_str(&format!("[{}]\n", rows.join(",")));
+        }
+    }
+    out
+}
+
+pub fn solve(hands: &[Hand], rules: &RuleSet) -> Result<usize> {
+    standings(hands, rules)?
+        .iter()
+        .try_fold(0_usize, |total, s| total.checked_add(s.winnings))
+        .ok_or_else(|| anyhow!("the total winnings are more than a usize can hold"))
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc::input::{as_windows, normalize};
+
+    use crate::{
+        generate::generate, parse_hands, report, solve, sort_hands, standings, Category,
+        Classifier, Format, Hand, Rank, RuleSet, Runs, Standard,
+    };
+
+    /// The standard Camel Cards rank, whatever classifier the rules use.
+    fn get_hand_rank(hand: &Hand, rules: &RuleSet) -> Rank {
+        let category = hand.category(&rules.clone().with_classifier(Standard));
+        [
+            Rank::FiveKind,
+            Rank::FourKind,
+            Rank::FullHouse,
+            Rank::ThreeKind,
+            Rank::TwoPair,
+            Rank::Pair,
+            Rank::HighCard,
+        ]
+        .into_iter()
+        .find(|rank| rank.category() == category)
+        .unwrap()
+    }
+
+    /// Whether `hand` beats `other` under `rules`.
+    fn compare(hand: &Hand, other: &Hand, rules: &RuleSet) -> bool {
+        hand.sort_key(rules) > other.sort_key(rules)
+    }
+
+    #[test]
+    fn parses_a_hand() {
+        let hand = "23456 123".parse::<Hand>();
+        dbg!(&hand);
+        assert!(hand.is_ok())
+    }
+
+    #[test]
+    fn line_endings_make_no_difference() {
+        let sample = "32T3K 765\nT55J5 684\nKK677 28\nKTJJT 220\nQQQJA 483";
+        let windows = normalize(&as_windows(sample)).into_owned();
+        for input in [sample, &windows] {
+            let hands = parse_hands(input).unwrap();
+            assert_eq!(solve(&hands, &RuleSet::standard()).unwrap(), 6440);
+            assert_eq!(solve(&hands, &RuleSet::jokers_wild()).unwrap(), 5905);
+        }
+    }
+
+    #[test]
+    fn agrees_with_generated_inputs() {
+        for seed in 0..20 {
+            let (input, part_one, part_two) = generate(300, seed);
+            let hands = parse_hands(&input).unwrap();
+            let standard = solve(&hands, &RuleSet::standard()).unwrap();
+            let jokers = solve(&hands, &RuleSet::jokers_wild()).unwrap();
+            assert_eq!(standard, part_one, "seed {seed}");
+            assert_eq!(jokers, part_two, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn says_which_line_a_bad_hand_is_on() {
+        let err = parse_hands("23456 1\n23456 x").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 7: expected a number, found \"x\""
+        );
+        assert!("23456".parse::<Hand>().is_err());
+    }
+
+    #[test]
+    fn evaluates_five_of_a_kind() {
+        let standard = RuleSet::standard();
+        let jokers = RuleSet::jokers_wild();
+        let (normal, with_wild) = (
+            "KKKKK 123".parse::<Hand>().unwrap(),
+            "KKKKJ 123".parse::<Hand>().unwrap(),
+        );
+        assert_eq!(get_hand_rank(&normal, &standard), Rank::FiveKind);
+        assert_eq!(get_hand_rank(&with_wild, &jokers), Rank::FiveKind);
+        assert_eq!(get_hand_rank(&with_wild, &standard), Rank::FourKind);
+    }
+
+    #[test]
+    fn evaluates_four_of_a_kind() {
+        let standard = RuleSet::standard();
+        let jokers = RuleSet::jokers_wild();
+        let (normal, with_wild) = (
+            "KKKTK 123".parse::<Hand>().unwrap(),
+            "KKKTJ 123".parse::<Hand>().unwrap(),
+        );
+        assert_eq!(get_hand_rank(&normal, &standard), Rank::FourKind);
+        assert_eq!(get_hand_rank(&with_wild, &jokers), Rank::FourKind);
+        assert_eq!(get_hand_rank(&with_wild, &standard), Rank::ThreeKind);
+    }
+
+    #[test]
+    fn evaluates_three_of_a_kind() {
+        let standard = RuleSet::standard();
+        let jokers = RuleSet::jokers_wild();
+        let (normal, with_wild) = (
+            "KK4TK 123".parse::<Hand>().unwrap(),
+            "KK4TJ 123".parse::<Hand>().unwrap(),
+        );
+        assert_eq!(get_hand_rank(&normal, &standard), Rank::ThreeKind);
+        assert_eq!(get_hand_rank(&with_wild, &jokers), Rank::ThreeKind);
+        assert_eq!(get_hand_rank(&with_wild, &standard), Rank::Pair);
+    }
+
+    #[test]
+    fn evaluates_full_house() {
+        let standard = RuleSet::standard();
+        let jokers = RuleSet::jokers_wild();
+        let (normal, with_wild) = (
+            "KK44K 123".parse::<Hand>().unwrap(),
+            "KK44J 123".parse::<Hand>().unwrap(),
+        );
+        assert_eq!(get_hand_rank(&normal, &standard), Rank::FullHouse);
+        assert_eq!(get_hand_rank(&with_wild, &jokers), Rank::FullHouse);
+        assert_eq!(get_hand_rank(&with_wild, &standard), Rank::TwoPair);
+    }
+
+    #[test]
+    fn evaluates_two_pair() {
+        let standard = RuleSet::standard();
+        let jokers = RuleSet::jokers_wild();
+        let (normal, with_wild) = (
+            "KK445 123".parse::<Hand>().unwrap(),
+            "KK42J 123".parse::<Hand>().unwrap(),
+        );
+        assert_eq!(get_hand_rank(&normal, &standard), Rank::TwoPair);
+        assert_eq!(get_hand_rank(&with_wild, &jokers), Rank::ThreeKind);
+        assert_eq!(get_hand_rank(&with_wild, &standard), Rank::Pair);
+    }
+
+    #[test]
+    fn evaluates_pair() {
+        let standard = RuleSet::standard();
+        let jokers = RuleSet::jokers_wild();
+        let (normal, with_wild) = (
+            "KK425 123".parse::<Hand>().unwrap(),
+            "K942J 123".parse::<Hand>().unwrap(),
+        );
+        assert_eq!(get_hand_rank(&normal, &standard), Rank::Pair);
+        assert_eq!(get_hand_rank(&with_wild, &jokers), Rank::Pair);
+        assert_eq!(get_hand_rank(&with_wild, &standard), Rank::HighCard);
+    }
+
+    #[test]
+    fn sorts_correctly() {
+        let standard = RuleSet::standard();
+        let jokers = RuleSet::jokers_wild();
+        let (normal, has_jack) = (
+            "KK425 123".parse::<Hand>().unwrap(),
+            "K922J 123".parse::<Hand>().unwrap(),
+        );
+
+        assert_eq!(get_hand_rank(&normal, &standard), Rank::Pair);
+        assert_eq!(get_hand_rank(&has_jack, &standard), Rank::Pair);
+        assert_eq!(get_hand_rank(&has_jack, &jokers), Rank::ThreeKind);
+
+        assert!(compare(&normal, &has_jack, &standard));
+        // Based on the fact that these are both one pair, the normal hand should be sorted to the
+        // first spot since there is a king in the 1 index of the cards
+        let mut hands = vec![has_jack.clone(), normal.clone()];
+        sort_hands(&mut hands, &jokers);
+        assert_eq!(hands[0], normal);
+        // Sorting again under other rules leaves nothing of the jokers behind
+        sort_hands(&mut hands, &standard);
+        assert_eq!(hands[0], has_jack);
+    }
+
+    #[test]
+    fn jokers_break_ties_as_the_weakest_card() {
+        let jokers = RuleSet::jokers_wild();
+        let (joker, queens) = (
+            "JQQQ2 1".parse::<Hand>().unwrap(),
+            "QQQQ2 1".parse::<Hand>().unwrap(),
+        );
+        assert_eq!(get_hand_rank(&joker, &jokers), Rank::FourKind);
+        assert!(compare(&queens, &joker, &jokers));
+        assert!(!compare(&joker, &queens, &jokers));
+    }
+
+    #[test]
+    fn supports_custom_wild_cards_and_orders() {
+        let twos_wild = RuleSet::standard().with_wild('2').unwrap();
+        let hand = "2KK3A 1".parse::<Hand>().unwrap();
+        assert_eq!(get_hand_rank(&hand, &twos_wild), Rank::ThreeKind);
+
+        let aces_low = RuleSet::standard().with_order("A23456789TJQK").unwrap();
+        let (ace, two) = (
+            "A3456 1".parse::<Hand>().unwrap(),
+            "23456 1".parse::<Hand>().unwrap(),
+        );
+        assert!(compare(&two, &ace, &aces_low));
+        assert!(compare(&ace, &two, &RuleSet::standard()));
+
+        assert!(RuleSet::standard().with_order("2234").is_err());
+        assert!(RuleSet::standard().with_order("").is_err());
+        assert!(RuleSet::standard().with_wild('X').is_err());
+    }
+
+    #[test]
+    fn plays_other_hand_sizes_and_alphabets() {
+        let rules = RuleSet::standard()
+            .with_order("abcde")
+            .unwrap()
+            .with_hand_size(3);
+        let hands: Vec<Hand> = ["aab 1", "ccc 10", "abc 100"]
+            .iter()
+            .map(|h| h.parse().unwrap())
+            .collect();
+        assert_eq!(get_hand_rank(&hands[1], &rules), Rank::ThreeKind);
+        assert_eq!(solve(&hands, &rules).unwrap(), 100 + 2 + 30);
+
+        let runs = rules.clone().with_classifier(Runs::new("abcde"));
+        assert_eq!(hands[2].category(&runs).name, "run");
+        assert_eq!(solve(&hands, &runs).unwrap(), 1 + 20 + 300);
+
+        assert!(solve(&hands, &RuleSet::standard()).is_err());
+        let seven: Hand = "KKKKKK2 1".parse().unwrap();
+        assert_eq!(get_hand_rank(&seven, &RuleSet::standard()), Rank::FiveKind);
+    }
+
+    /// Any hand holding an ace beats every hand without one, and nothing else counts.
+    #[derive(Debug)]
+    struct AcesHigh;
+
+    impl Classifier for AcesHigh {
+        fn classify(&self, cards: &[char], _groups: &[usize]) -> Category {
+            if cards.contains(&'A') {
+                return Category {
+                    strength: 70,
+                    name: "holding an ace",
+                };
+            }
+            Rank::HighCard.category()
+        }
+    }
+
+    #[test]
+    fn ranks_with_classifiers_defined_elsewhere() {
+        let hands = parse_hands("23456 1\nA2345 10\nKKKKK 100").unwrap();
+        let rules = RuleSet::standard().with_classifier(AcesHigh);
+        assert_eq!(hands[1].category(&rules).name, "holding an ace");
+        assert_eq!(solve(&hands, &rules).unwrap(), 1 + 200 + 30);
+    }
+
+    #[test]
+    fn refuses_winnings_that_overflow() {
+        let hands = parse_hands("23456 1\n34567 18446744073709551615").unwrap();
+        let err = solve(&hands, &RuleSet::standard()).unwrap_err();
+        assert_eq!(err.to_string(), "34567 wins more than a usize can hold");
+        let hands = parse_hands("23456 18446744073709551615\n34567 1").unwrap();
+        assert!(solve(&hands, &RuleSet::standard()).is_err());
+    }
+
+    #[test]
+    fn reports_standings() {
+        let hands = parse_hands("32T3K 765\nT55J5 684\nKK677 28\nKTJJT 220\nQQQJA 483").unwrap();
+        let table = standings(&hands, &RuleSet::jokers_wild()).unwrap();
+        assert_eq!(table.iter().map(|s| s.winnings).sum::<usize>(), 5905);
+
+        let last = table.last().unwrap();
+        assert_eq!(
+            (last.position, last.cards.as_str(), last.category),
+            (5, "KTJJT", "four of a kind")
+        );
+        assert_eq!(last.substitution.as_deref(), Some("KTTTT"));
+        assert_eq!(table[0].substitution, None);
+
+        let csv = report(&table, Format::Csv);
+        assert_eq!(csv.lines().count(), 6);
+        assert!(csv.contains("5,KTJJT,four of a kind,KTTTT,220,1100"));
+        let json = report(&table, Format::Json);
+        assert!(json.starts_with("[{\"position\":1,\"hand\":\"32T3K\""));
+        assert!(json.contains("\"substitution\":null"));
+        assert!(report(&table, Format::Text).ends_with("total winnings: 5905\n"));
+    }
+
+    #[test]
+    fn escapes_awkward_cards_in_reports() {
+        let rules = RuleSet::standard().with_order("ab,\"\u{1}").unwrap();
+        let hands = parse_hands("ab,\"\u{1} 1").unwrap();
+        let table = standings(&hands, &rules).unwrap();
+
+        let csv = report(&table, Format::Csv);
+        assert_eq!(
+            csv.lines().nth(1),
+            Some("1,\"ab,\"\"\u{1}\",high card,,1,1")
+        );
+        let json = report(&table, Format::Json);
+        assert!(json.contains("\"hand\":\"ab,\\\"\\u0001\""), "{json}");
+    }
+
+    #[test]
+    fn substitutes_the_strongest_card_when_all_are_wild() {
+        let hand: Hand = "JJJJJ 1".parse().unwrap();
+        assert_eq!(
+            hand.substitution(&RuleSet::jokers_wild()).as_deref(),
+            Some("AAAAA")
+        );
+        let tie: Hand = "J2K2K 1".parse().unwrap();
+        assert_eq!(
+            tie.substitution(&RuleSet::jokers_wild()).as_deref(),
+            Some("K2K2K")
+        );
+    }
+
+    const CARDS: [char; 13] = [
+        '2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A',
+    ];
+
+    /// Every multiset of `size` cards, as indices into `CARDS`.
+    fn multisets(size: usize) -> Vec<Vec<usize>> {
+        let mut out = vec![vec![]];
+        for _ in 0..size {
+            out = out
+                .iter()
+                .flat_map(|m: &Vec<usize>| {
+                    let from = m.last().copied().unwrap_or(0);
+                    (from..CARDS.len()).map(move |i| [m.clone(), vec![i]].concat())
+                })
+                .collect();
+        }
+        out
+    }
+
+    fn rank(cards: impl Iterator<Item = char>, rules: &RuleSet) -> Rank {
+        let hand = format!("{} 1", String::from_iter(cards)).parse().unwrap();
+        get_hand_rank(&hand, rules)
+    }
+
+    /// Every way of filling in the jokers, ranked without them, keeping the best. The order
+    /// of the cards doesn't change the rank, so each multiset of fillings is enough.
+    fn best_substitution(cards: &[char]) -> Rank {
+        let standard = RuleSet::standard();
+        let real = cards.iter().copied().filter(|c| *c != 'J');
+        let jokers = cards.len() - real.clone().count();
+        multisets(jokers)
+            .iter()
+            .map(|fill| {
+                rank(
+                    real.clone().chain(fill.iter().map(|i| CARDS[*i])),
+                    &standard,
+                )
+            })
+            .min()
+            .unwrap()
+    }
+
+    #[test]
+    fn jokers_rank_like_their_best_substitution() {
+        let jokers = RuleSet::jokers_wild();
+        for hand in multisets(5) {
+            let cards: Vec<char> = hand.iter().map(|i| CARDS[*i]).collect();
+            assert_eq!(
+                rank(cards.iter().copied(), &jokers),
+                best_substitution(&cards),
+                "{cards:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|line: &str| {
+    if let Err(err) = day8::Node::parse(line) {
+        assert!(err.location.is_some(), "{err:?}");
+    }
+    let _ = day8::create_node_hash(line);
+});
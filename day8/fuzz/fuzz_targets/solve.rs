@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = day8::solve_part_one(input, |l| l == "AAA", |l| l == "ZZZ", None);
+    let _ = day8::solve_part_two(input);
+});
@@ -0,0 +1,91 @@
+//! Made-up ghost networks of any size, with answers worked out from the loops each ghost was
+//! built to run round.
+
+use std::collections::HashSet;
+
+use aoc::{math::lcm, rng::Rng};
+
+/// Letters for the middle of a label, leaving `A` and `Z` to mark starts and targets.
+const LETTERS: &[u8] = b"BCDEFGHIJKLMNOPQRSTUVWXY";
+
+fn label(rng: &mut Rng, width: usize, last: Option<u8>) -> String {
+    let mut label: Vec<u8> = (0..width).map(|_| *rng.pick(LETTERS)).collect();
+    if let Some(last) = last {
+        label[width - 1] = last;
+    }
+    String::from_utf8(label).unwrap()
+}
+
+/// A network with `size` ghosts, and the answers to both parts for it.
+///
+/// Each ghost walks a loop that takes a whole number of passes through the directions to get
+/// round, hitting its one `Z` node at the end of every lap and going on from there just as it
+/// did from its start, like the real inputs do. Ghost one starts at `AAA` and loops through
+/// `ZZZ`, so part one is the length of its loop and part two is when all the loops line up.
+pub fn generate(size: usize, seed: u64) -> (String, usize, u128) {
+    let mut rng = Rng::new(seed);
+    let passes = [1, 2, 3, 5, 7, 11, 13];
+    let directions: Vec<u8> = (0..rng.range(2..12)).map(|_| *rng.pick(b"LR")).collect();
+    let laps: Vec<usize> = (0..size.max(1))
+        .map(|_| directions.len() * rng.pick(&passes))
+        .collect();
+
+    // Labels get longer once three letters can't go round everyone
+    let nodes: usize = laps.iter().map(|lap| lap + 1).sum();
+    let mut width = 3;
+    while LETTERS.len().pow(width as u32 - 1) < 2 * nodes {
+        width += 1;
+    }
+
+    let mut used = HashSet::from(["AAA".to_string(), "ZZZ".to_string()]);
+    let mut fresh = |rng: &mut Rng, last| loop {
+        let label = label(rng, width, last);
+        if used.insert(label.clone()) {
+            return label;
+        }
+    };
+
+    let mut lines = Vec::with_capacity(nodes);
+    for (ghost, &lap) in laps.iter().enumerate() {
+        // Step `i` of the loop goes from `path[i]` to `path[i + 1]`
+        let mut path = Vec::with_capacity(lap + 1);
+        path.push(match ghost {
+            0 => "AAA".to_string(),
+            _ => fresh(&mut rng, Some(b'A')),
+        });
+        for _ in 1..lap {
+            path.push(fresh(&mut rng, None));
+        }
+        path.push(match ghost {
+            0 => "ZZZ".to_string(),
+            _ => fresh(&mut rng, Some(b'Z')),
+        });
+
+        let mut edges: Vec<(String, String)> = (0..lap)
+            .map(|step| {
+                let next = path[step + 1].clone();
+                // Wherever the walk doesn't go, as long as it's part of the same ghost's loop
+                let other = rng.pick(&path[1..]).clone();
+                match directions[step % directions.len()] {
+                    b'L' => (next, other),
+                    _ => (other, next),
+                }
+            })
+            .collect();
+        // The target carries on exactly as the start did
+        edges.push(edges[0].clone());
+
+        for (node, (left, right)) in path.iter().zip(edges) {
+            lines.push(format!("{node} = ({left}, {right})"));
+        }
+    }
+    rng.shuffle(&mut lines);
+
+    let part_two = laps.iter().map(|&lap| lap as u128).fold(1, lcm);
+    let text = format!(
+        "{}\n\n{}",
+        String::from_utf8(directions).unwrap(),
+        lines.join("\n")
+    );
+    (text, laps[0], part_two)
+}
@@ -0,0 +1,1052 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Write,
+};
+
+use aoc::{
+    math::{checked_lcm, crt},
+    parse::{enclosed, split_pair, ParseError},
+};
+
+pub mod generate;
+
+#[derive(Debug)]
+pub struct Node<'a> {
+    left: &'a str,
+    right: &'a str,
+}
+
+/// Everything that can stop a walk through the network from giving an answer.
+#[derive(Debug, PartialEq)]
+pub enum NetworkError {
+    DanglingReference {
+        node: String,
+        label: String,
+    },
+    BadDirection(char),
+    NoDirections,
+    NoStart,
+    AmbiguousStart(Vec<String>),
+    /// The walk came back to a node at the same point in the directions without finding a
+    /// target, so it would go round forever.
+    Unreachable {
+        steps: usize,
+    },
+    /// The ghosts would line up eventually, but after more steps than a `u128` can count.
+    TooManySteps,
+    /// The ghosts stand on so many `Z` nodes between them that there are more ways for them to
+    /// line up than it's sensible to try.
+    TooManyCombinations,
+    Syntax(ParseError),
+}
+
+impl From<ParseError> for NetworkError {
+    fn from(e: ParseError) -> Self {
+        NetworkError::Syntax(e)
+    }
+}
+
+/// One step of a walk, handed to a tracer as it happens.
+#[derive(Debug)]
+pub struct Step<'a> {
+    /// How many steps have been taken, this one included.
+    pub count: usize,
+    pub from: &'a str,
+    pub direction: Direction,
+    pub to: &'a str,
+}
+
+impl<'a> Node<'a> {
+    pub fn parse(s: &'a str) -> Result<Self, ParseError> {
+        let parse = || {
+            let (_, targets) = split_pair(s, "=")?;
+            let (left, right) = split_pair(enclosed(targets, "(", ")")?, ",")?;
+            Ok(Node { left, right })
+        };
+        parse().map_err(|e: ParseError| e.locate(s))
+    }
+}
+
+/// Every node by its label, borrowing the labels straight from the input.
+pub fn create_node_hash(input: &str) -> Result<HashMap<&str, Node<'_>>, ParseError> {
+    input
+        .lines()
+        .skip(2)
+        .map(|line| {
+            let (label, _) = split_pair(line, "=")?;
+            Ok((label, Node::parse(line)?))
+        })
+        .collect::<Result<_, ParseError>>()
+        .map_err(|e| e.locate(input))
+}
+
+/// The network with every label swapped for a dense index, so a step is two array lookups
+/// instead of hashing a string.
+#[derive(Debug)]
+pub struct Graph<'a> {
+    labels: Vec<&'a str>,
+    left: Vec<usize>,
+    right: Vec<usize>,
+}
+
+impl<'a> Graph<'a> {
+    /// Interns every label. Fails if a node points at a label that has no node of its own.
+    pub fn new(nodes: &HashMap<&'a str, Node<'a>>) -> Result<Graph<'a>, NetworkError> {
+        let mut labels: Vec<&str> = nodes.keys().copied().collect();
+        labels.sort_unstable();
+
+        let mut left = Vec::with_capacity(labels.len());
+        let mut right = Vec::with_capacity(labels.len());
+        for label in &labels {
+            let node = &nodes[label];
+            let lookup = |to: &str| {
+                labels
+                    .binary_search(&to)
+                    .map_err(|_| NetworkError::DanglingReference {
+                        node: label.to_string(),
+                        label: to.to_string(),
+                    })
+            };
+            left.push(lookup(node.left)?);
+            right.push(lookup(node.right)?);
+        }
+
+        Ok(Graph {
+            labels,
+            left,
+            right,
+        })
+    }
+
+    pub fn id(&self, label: &str) -> Option<usize> {
+        self.labels.binary_search(&label).ok()
+    }
+
+    pub fn label(&self, id: usize) -> &str {
+        self.labels[id]
+    }
+
+    fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    fn step(&self, id: usize, direction: Direction) -> usize {
+        match direction {
+            Direction::Left => self.left[id],
+            Direction::Right => self.right[id],
+        }
+    }
+
+    /// Graphviz source for the network. Start and target nodes are filled in, and the edges
+    /// in `path` (a node and the direction taken out of it) are drawn thick and blue.
+    pub fn to_dot(
+        &self,
+        is_start: impl Fn(&str) -> bool,
+        is_target: impl Fn(&str) -> bool,
+        path: &HashSet<(String, Direction)>,
+    ) -> String {
+        let quote = |label: &str| format!("\"{}\"", label.replace('"', "\\\""));
+        let mut dot = String::from("digraph network {\n");
+        for (id, label) in self.labels.iter().enumerate() {
+            if is_start(label) {
+                let _ = writeln!(
+                    dot,
+                    "  {} [style=filled, fillcolor=palegreen];",
+                    quote(label)
+                );
+            } else if is_target(label) {
+                let _ = writeln!(dot, "  {} [style=filled, fillcolor=salmon];", quote(label));
+            }
+            for direction in [Direction::Left, Direction::Right] {
+                let name = match direction {
+                    Direction::Left => "L",
+                    Direction::Right => "R",
+                };
+                let highlight = match path.contains(&(label.to_string(), direction)) {
+                    true => ", color=blue, penwidth=3",
+                    false => "",
+                };
+                let _ = writeln!(
+                    dot,
+                    "  {} -> {} [label=\"{name}\"{highlight}];",
+                    quote(label),
+                    quote(self.label(self.step(id, direction)))
+                );
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// One line per node: the node, then where left and right take it.
+    pub fn to_adjacency(&self) -> String {
+        let mut out = String::new();
+        for (id, label) in self.labels.iter().enumerate() {
+            let _ = writeln!(
+                out,
+                "{label} {} {}",
+                self.label(self.left[id]),
+                self.label(self.right[id])
+            );
+        }
+        out
+    }
+}
+
+/// Which way to leave a node, as spelled out by the `L` and `R` instructions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Left,
+    Right,
+}
+
+pub fn parse_directions(directions: &str) -> Result<Vec<Direction>, NetworkError> {
+    let directions = directions
+        .chars()
+        .map(|c| match c {
+            'L' => Ok(Direction::Left),
+            'R' => Ok(Direction::Right),
+            c => Err(NetworkError::BadDirection(c)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if directions.is_empty() {
+        return Err(NetworkError::NoDirections);
+    }
+    Ok(directions)
+}
+
+/// Counts the steps from the one node matching `is_start` to the first node matching
+/// `is_target`. Every step is handed to `trace`, if there is one.
+fn traverse_nodes(
+    directions: &[Direction],
+    graph: &Graph,
+    is_start: impl Fn(&str) -> bool,
+    is_target: impl Fn(&str) -> bool,
+    mut trace: Option<&mut dyn FnMut(&Step)>,
+) -> Result<usize, NetworkError> {
+    let starts: Vec<usize> = (0..graph.len())
+        .filter(|id| is_start(graph.label(*id)))
+        .collect();
+    let mut node = match starts[..] {
+        [] => return Err(NetworkError::NoStart),
+        [start] => start,
+        _ => {
+            let labels = starts.iter().map(|id| graph.label(*id).to_string());
+            return Err(NetworkError::AmbiguousStart(labels.collect()));
+        }
+    };
+
+    let mut seen = vec![false; graph.len() * directions.len()];
+    let mut count = 0;
+    loop {
+        let instruction = count % directions.len();
+        let state = node * directions.len() + instruction;
+        if seen[state] {
+            return Err(NetworkError::Unreachable { steps: count });
+        }
+        seen[state] = true;
+
+        let direction = directions[instruction];
+        let next = graph.step(node, direction);
+        count += 1;
+        if let Some(trace) = trace.as_mut() {
+            trace(&Step {
+                count,
+                from: graph.label(node),
+                direction,
+                to: graph.label(next),
+            });
+        }
+        if is_target(graph.label(next)) {
+            return Ok(count);
+        }
+        node = next;
+    }
+}
+
+/// Counts the steps from the one node whose label matches `is_start` to the first whose label
+/// matches `is_target`, which for the puzzle are `AAA` and `ZZZ`.
+pub fn solve_part_one(
+    input: &str,
+    is_start: impl Fn(&str) -> bool,
+    is_target: impl Fn(&str) -> bool,
+    trace: Option<&mut dyn FnMut(&Step)>,
+) -> Result<usize, NetworkError> {
+    let directions: String = input.lines().take(1).collect();
+    let directions = parse_directions(&directions)?;
+    let graph = Graph::new(&create_node_hash(input)?)?;
+    traverse_nodes(&directions, &graph, is_start, is_target, trace)
+}
+
+/// Answers "where is a walker after k steps" without taking them, for walkers starting at
+/// the beginning of the directions.
+///
+/// `prefix[r]` says where each node ends up after the first `r` directions, and `jumps[j]`
+/// where it ends up after `2^j` full passes through the directions. Any `k` splits into whole
+/// passes, taken a power of two at a time, followed by a prefix.
+#[derive(Debug)]
+pub struct JumpTable {
+    prefix: Vec<Vec<usize>>,
+    jumps: Vec<Vec<usize>>,
+}
+
+impl JumpTable {
+    /// Fails with [`NetworkError::NoDirections`] when there are no directions to repeat.
+    pub fn new(graph: &Graph, directions: &[Direction]) -> Result<Self, NetworkError> {
+        if directions.is_empty() {
+            return Err(NetworkError::NoDirections);
+        }
+        let mut prefix = vec![(0..graph.len()).collect::<Vec<usize>>()];
+        for direction in directions {
+            let last = prefix.last().expect("starts with the identity");
+            prefix.push(last.iter().map(|n| graph.step(*n, *direction)).collect());
+        }
+
+        let mut jumps = vec![prefix[directions.len()].clone()];
+        for _ in 1..u64::BITS {
+            let last = jumps.last().expect("starts with one pass");
+            jumps.push(last.iter().map(|n| last[*n]).collect());
+        }
+
+        Ok(JumpTable { prefix, jumps })
+    }
+
+    pub fn position(&self, start: usize, steps: u64) -> usize {
+        let period = (self.prefix.len() - 1) as u64;
+        let (mut passes, rest) = (steps / period, steps % period);
+        let mut node = start;
+        let mut level = 0;
+        while passes > 0 {
+            if passes & 1 == 1 {
+                node = self.jumps[level][node];
+            }
+            passes >>= 1;
+            level += 1;
+        }
+        self.prefix[rest as usize][node]
+    }
+}
+
+/// The path one ghost takes. Ghosts only ever see a finite number of (node, instruction)
+/// states, so every path settles into a loop after a run-in of `offset` steps.
+#[derive(Debug, PartialEq)]
+pub struct Ghost {
+    offset: usize,
+    length: usize,
+    /// Steps before the loop where the ghost stands on a `Z` node.
+    run_in_hits: Vec<usize>,
+    /// Steps within the first time round the loop where the ghost stands on a `Z` node.
+    loop_hits: Vec<usize>,
+}
+
+impl Ghost {
+    fn follow(start: usize, directions: &[Direction], graph: &Graph) -> Ghost {
+        // When each (node, instruction) state was first seen, indexed by node then instruction
+        let mut seen = vec![usize::MAX; graph.len() * directions.len()];
+        let mut hits = Vec::new();
+        let mut node = start;
+        let mut step = 0;
+
+        loop {
+            let instruction = step % directions.len();
+            let state = node * directions.len() + instruction;
+            if seen[state] != usize::MAX {
+                let first = seen[state];
+                let (run_in_hits, loop_hits) = hits.iter().partition(|hit| **hit < first);
+                return Ghost {
+                    offset: first,
+                    length: step - first,
+                    run_in_hits,
+                    loop_hits,
+                };
+            }
+            seen[state] = step;
+            if graph.label(node).ends_with('Z') {
+                hits.push(step);
+            }
+
+            node = graph.step(node, directions[instruction]);
+            step += 1;
+        }
+    }
+
+    fn is_on_z(&self, step: usize) -> bool {
+        if step < self.offset {
+            return self.run_in_hits.contains(&step);
+        }
+        let folded = self.offset + (step - self.offset) % self.length;
+        self.loop_hits.contains(&folded)
+    }
+
+    /// The shape the puzzle is built around: one `Z` per loop, reached exactly every
+    /// `length` steps from the start.
+    pub fn is_simple(&self) -> bool {
+        self.run_in_hits.is_empty() && self.loop_hits == [self.length]
+    }
+}
+
+/// Past this many combinations of loop hits, [`all_on_z`] gives up rather than run out of
+/// memory trying them all.
+const MAX_COMBINATIONS: usize = 1 << 20;
+
+/// The first step where every ghost stands on a `Z` node at once, or `None` if they never do.
+fn all_on_z(ghosts: &[Ghost]) -> Result<Option<u128>, NetworkError> {
+    if ghosts.is_empty() {
+        return Err(NetworkError::NoStart);
+    }
+    if ghosts.iter().all(Ghost::is_simple) {
+        return ghosts
+            .iter()
+            .try_fold(1, |acc, g| checked_lcm(acc, g.length as u128))
+            .map(Some)
+            .ok_or(NetworkError::TooManySteps);
+    }
+
+    // Any answer before every ghost has reached its loop has to be a run-in hit for one of
+    // them, and there are only a handful of those
+    let mut run_in: Vec<usize> = ghosts
+        .iter()
+        .flat_map(|g| g.run_in_hits.iter().copied())
+        .collect();
+    run_in.sort_unstable();
+    if let Some(step) = run_in
+        .into_iter()
+        .find(|step| ghosts.iter().all(|g| g.is_on_z(*step)))
+    {
+        return Ok(Some(step as u128));
+    }
+
+    // Past that, each ghost is somewhere in its loop, so try every combination of loop hits
+    let settled = ghosts.iter().map(|g| g.offset).max().unwrap_or(0) as u128;
+    let mut combos: Vec<(u128, u128)> = vec![(0, 1)];
+    for ghost in ghosts {
+        if combos.len().saturating_mul(ghost.loop_hits.len()) > MAX_COMBINATIONS {
+            return Err(NetworkError::TooManyCombinations);
+        }
+        // Every combination shares a modulus, so checking it fits here leaves `crt` failing
+        // only when the hits can't line up
+        let length = ghost.length as u128;
+        checked_lcm(combos[0].1, length).ok_or(NetworkError::TooManySteps)?;
+        combos = combos
+            .iter()
+            .flat_map(|combo| {
+                ghost
+                    .loop_hits
+                    .iter()
+                    .filter_map(move |hit| crt(*combo, (*hit as u128 % length, length)))
+            })
+            .collect();
+        if combos.is_empty() {
+            return Ok(None);
+        }
+    }
+    combos
+        .into_iter()
+        .map(|(residue, modulus)| {
+            if residue >= settled {
+                return Some(residue);
+            }
+            let laps = (settled - residue).div_ceil(modulus);
+            laps.checked_mul(modulus)?.checked_add(residue)
+        })
+        .try_fold(None, |best: Option<u128>, step| {
+            let step = step?;
+            Some(Some(best.map_or(step, |best| best.min(step))))
+        })
+        .ok_or(NetworkError::TooManySteps)
+}
+
+pub fn ghosts(graph: &Graph, directions: &[Direction]) -> Vec<Ghost> {
+    (0..graph.len())
+        .filter(|id| graph.label(*id).ends_with('A'))
+        .map(|start| Ghost::follow(start, directions, graph))
+        .collect()
+}
+
+/// `None` when the ghosts never all line up on `Z` nodes at once.
+pub fn solve_part_two(input: &str) -> Result<Option<u128>, NetworkError> {
+    let directions: String = input.lines().take(1).collect();
+    let directions = parse_directions(&directions)?;
+    let graph = Graph::new(&create_node_hash(input)?)?;
+    all_on_z(&ghosts(&graph, &directions))
+}
+
+/// How a walker finds its way through the network: picking left or right as it pleases, or
+/// strictly following the instructions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Free,
+    Fixed,
+}
+
+/// Where a walker can be. Picking freely that's just a node; following the instructions it's
+/// the node and how far through the instructions it is, numbered the same way as in `Ghost`.
+struct States<'a> {
+    graph: &'a Graph<'a>,
+    directions: &'a [Direction],
+    mode: Mode,
+}
+
+impl States<'_> {
+    fn len(&self) -> usize {
+        match self.mode {
+            Mode::Free => self.graph.len(),
+            Mode::Fixed => self.graph.len() * self.directions.len(),
+        }
+    }
+
+    fn start(&self, node: usize) -> usize {
+        match self.mode {
+            Mode::Free => node,
+            Mode::Fixed => node * self.directions.len(),
+        }
+    }
+
+    fn node(&self, state: usize) -> usize {
+        match self.mode {
+            Mode::Free => state,
+            Mode::Fixed => state / self.directions.len(),
+        }
+    }
+
+    fn successors(&self, state: usize) -> Vec<usize> {
+        match self.mode {
+            Mode::Free => vec![self.graph.left[state], self.graph.right[state]],
+            Mode::Fixed => {
+                let count = self.directions.len();
+                let (node, instruction) = (state / count, state % count);
+                let next = self.graph.step(node, self.directions[instruction]);
+                vec![next * count + (instruction + 1) % count]
+            }
+        }
+    }
+
+    /// Tarjan's algorithm, with an explicit stack since the instruction-following state
+    /// space is far too deep to recurse through.
+    fn strongly_connected(&self) -> Vec<Vec<usize>> {
+        let len = self.len();
+        let mut index = vec![usize::MAX; len];
+        let mut low = vec![0; len];
+        let mut on_stack = vec![false; len];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut counter = 0;
+
+        for root in 0..len {
+            if index[root] != usize::MAX {
+                continue;
+            }
+            index[root] = counter;
+            low[root] = counter;
+            counter += 1;
+            stack.push(root);
+            on_stack[root] = true;
+            // Each frame is a state and how many of its successors have been looked at
+            let mut frames = vec![(root, 0)];
+
+            while let Some((state, child)) = frames.pop() {
+                if let Some(&next) = self.successors(state).get(child) {
+                    frames.push((state, child + 1));
+                    if index[next] == usize::MAX {
+                        index[next] = counter;
+                        low[next] = counter;
+                        counter += 1;
+                        stack.push(next);
+                        on_stack[next] = true;
+                        frames.push((next, 0));
+                    } else if on_stack[next] {
+                        low[state] = low[state].min(index[next]);
+                    }
+                    continue;
+                }
+
+                if let Some(&(parent, _)) = frames.last() {
+                    low[parent] = low[parent].min(low[state]);
+                }
+                if low[state] == index[state] {
+                    let mut component = Vec::new();
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == state {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+        components
+    }
+
+    /// Every state that can get to a `Z` node, found by walking the edges backwards.
+    fn reaching_z(&self) -> Vec<bool> {
+        let mut incoming = vec![Vec::new(); self.len()];
+        for state in 0..self.len() {
+            for next in self.successors(state) {
+                incoming[next].push(state);
+            }
+        }
+        let mut reaches = vec![false; self.len()];
+        let mut queue: VecDeque<usize> = (0..self.len())
+            .filter(|state| self.graph.label(self.node(*state)).ends_with('Z'))
+            .collect();
+        for state in &queue {
+            reaches[*state] = true;
+        }
+        while let Some(state) = queue.pop_front() {
+            for previous in &incoming[state] {
+                if !reaches[*previous] {
+                    reaches[*previous] = true;
+                    queue.push_back(*previous);
+                }
+            }
+        }
+        reaches
+    }
+
+    /// The `Z` nodes a walker setting off from `node` can get to.
+    fn targets_from(&self, node: usize) -> Vec<usize> {
+        let start = self.start(node);
+        let mut visited = vec![false; self.len()];
+        visited[start] = true;
+        let mut queue = VecDeque::from([start]);
+        let mut targets = Vec::new();
+        while let Some(state) = queue.pop_front() {
+            if self.graph.label(self.node(state)).ends_with('Z') {
+                targets.push(self.node(state));
+            }
+            for next in self.successors(state) {
+                if !visited[next] {
+                    visited[next] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+        targets.sort_unstable();
+        targets.dedup();
+        targets
+    }
+}
+
+/// The shape of the network as one kind of walker sees it. Everything is in node ids.
+#[derive(Debug, PartialEq)]
+pub struct Analysis {
+    mode: Mode,
+    /// Groups of nodes a walker can go round and round in forever.
+    cycles: Vec<Vec<usize>>,
+    /// The `Z` nodes each `A` node can get to.
+    reachable: Vec<(usize, Vec<usize>)>,
+    /// Nodes no `Z` node can be reached from, however far into the instructions the walker is.
+    dead_ends: Vec<usize>,
+    /// Edges from a node straight back to itself that a walker can actually take.
+    self_loops: Vec<(usize, Direction)>,
+}
+
+impl Analysis {
+    pub fn new(graph: &Graph, directions: &[Direction], mode: Mode) -> Analysis {
+        let states = States {
+            graph,
+            directions,
+            mode,
+        };
+
+        let mut cycles: Vec<Vec<usize>> = states
+            .strongly_connected()
+            .into_iter()
+            .filter(|c| c.len() > 1 || states.successors(c[0]).contains(&c[0]))
+            .map(|component| {
+                let mut nodes: Vec<usize> = component.iter().map(|s| states.node(*s)).collect();
+                nodes.sort_unstable();
+                nodes.dedup();
+                nodes
+            })
+            .collect();
+        cycles.sort_unstable();
+        cycles.dedup();
+
+        let reachable = (0..graph.len())
+            .filter(|id| graph.label(*id).ends_with('A'))
+            .map(|start| (start, states.targets_from(start)))
+            .collect();
+
+        let reaches = states.reaching_z();
+        let mut alive = vec![false; graph.len()];
+        for (state, reaches) in reaches.into_iter().enumerate() {
+            alive[states.node(state)] |= reaches;
+        }
+        let dead_ends = (0..graph.len()).filter(|id| !alive[*id]).collect();
+
+        let taken: Vec<Direction> = match mode {
+            Mode::Free => vec![Direction::Left, Direction::Right],
+            Mode::Fixed => [Direction::Left, Direction::Right]
+                .into_iter()
+                .filter(|d| directions.contains(d))
+                .collect(),
+        };
+        let self_loops = (0..graph.len())
+            .flat_map(|id| taken.iter().map(move |d| (id, *d)))
+            .filter(|(id, d)| graph.step(*id, *d) == *id)
+            .collect();
+
+        Analysis {
+            mode,
+            cycles,
+            reachable,
+            dead_ends,
+            self_loops,
+        }
+    }
+
+    pub fn report(&self, graph: &Graph) -> String {
+        let names = |ids: &[usize]| {
+            ids.iter()
+                .map(|id| graph.label(*id))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let mut out = String::new();
+        let _ = writeln!(out, "{:?} walk", self.mode);
+        let _ = writeln!(out, "  cycles: {}", self.cycles.len());
+        for cycle in &self.cycles {
+            let _ = writeln!(out, "    {}", names(cycle));
+        }
+        for (start, targets) in &self.reachable {
+            let _ = writeln!(out, "  {} reaches: {}", graph.label(*start), names(targets));
+        }
+        let _ = writeln!(out, "  dead ends: {}", names(&self.dead_ends));
+        let loops: Vec<String> = self
+            .self_loops
+            .iter()
+            .map(|(id, d)| format!("{}({d:?})", graph.label(*id)))
+            .collect();
+        let _ = writeln!(out, "  self loops: {}", loops.join(" "));
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use aoc::{
+        alloc::{allocations, Counting},
+        input::{as_windows, normalize},
+    };
+
+    use crate::{
+        all_on_z, create_node_hash, generate::generate, parse_directions, solve_part_one,
+        solve_part_two, Analysis, Direction, Ghost, Graph, JumpTable, Mode, NetworkError, Step,
+    };
+
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+
+    fn is_aaa(label: &str) -> bool {
+        label == "AAA"
+    }
+
+    fn is_zzz(label: &str) -> bool {
+        label == "ZZZ"
+    }
+
+    const GHOSTS: &str = "LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)";
+
+    #[test]
+    fn solves_part_one() {
+        let input = "LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)";
+        assert_eq!(solve_part_one(input, is_aaa, is_zzz, None), Ok(6));
+
+        let mut steps = Vec::new();
+        let mut trace =
+            |step: &Step| steps.push(format!("{}{:?}{}", step.from, step.direction, step.to));
+        solve_part_one(input, is_aaa, is_zzz, Some(&mut trace)).unwrap();
+        assert_eq!(steps[0], "AAALeftBBB");
+        assert_eq!(steps.len(), 6);
+    }
+
+    #[test]
+    fn agrees_with_generated_inputs() {
+        for seed in 0..20 {
+            let (input, part_one, part_two) = generate(6, seed);
+            assert_eq!(
+                solve_part_one(&input, is_aaa, is_zzz, None),
+                Ok(part_one),
+                "seed {seed}"
+            );
+            assert_eq!(solve_part_two(&input), Ok(Some(part_two)), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn reports_broken_networks() {
+        let unreachable = "LR\n\nAAA = (BBB, BBB)\nBBB = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)";
+        assert_eq!(
+            solve_part_one(unreachable, is_aaa, is_zzz, None),
+            Err(NetworkError::Unreachable { steps: 2 })
+        );
+
+        let dangling = "L\n\nAAA = (BBB, ZZZ)\nZZZ = (ZZZ, ZZZ)";
+        assert_eq!(
+            solve_part_one(dangling, is_aaa, is_zzz, None),
+            Err(NetworkError::DanglingReference {
+                node: "AAA".to_string(),
+                label: "BBB".to_string()
+            })
+        );
+
+        let no_start = "L\n\nZZZ = (ZZZ, ZZZ)";
+        assert_eq!(
+            solve_part_one(no_start, is_aaa, is_zzz, None),
+            Err(NetworkError::NoStart)
+        );
+        assert_eq!(
+            solve_part_one("LX\n\nAAA = (AAA, AAA)", is_aaa, is_zzz, None),
+            Err(NetworkError::BadDirection('X'))
+        );
+
+        let Err(NetworkError::Syntax(err)) =
+            solve_part_one("L\n\nAAA = (AAA, AAA\n", is_aaa, is_zzz, None)
+        else {
+            panic!("a missing bracket should be a syntax error");
+        };
+        assert_eq!(err.location, Some((3, 7)));
+    }
+
+    #[test]
+    fn parsing_borrows_the_labels() {
+        let lines: Vec<String> = (0..1000)
+            .map(|n| format!("N{n:03} = (N{:03}, N{:03})", (n + 1) % 1000, n))
+            .collect();
+        let input = format!("LR\n\n{}", lines.join("\n"));
+        let (nodes, parsing) = allocations(|| create_node_hash(&input).unwrap());
+        let (graph, interning) = allocations(|| Graph::new(&nodes).unwrap());
+        assert_eq!(graph.len(), 1000);
+        // Only the map and the graph's vectors allocate, never one string per label
+        assert!(parsing < 50, "{parsing} allocations parsing");
+        assert!(interning < 10, "{interning} allocations interning");
+    }
+
+    #[test]
+    fn starts_and_stops_wherever_asked() {
+        let input = "LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)";
+        let steps = solve_part_one(input, |l| l == "BBB", |l| l == "AAA", None);
+        assert_eq!(steps, Ok(1));
+        let ambiguous = solve_part_one(input, |l| l != "ZZZ", |_| true, None);
+        assert!(matches!(ambiguous, Err(NetworkError::AmbiguousStart(_))));
+    }
+
+    #[test]
+    fn solves_the_sample() {
+        assert_eq!(solve_part_two(GHOSTS), Ok(Some(6)));
+    }
+
+    #[test]
+    fn line_endings_make_no_difference() {
+        let windows = normalize(&as_windows(GHOSTS)).into_owned();
+        assert_eq!(solve_part_two(&windows), Ok(Some(6)));
+        let single = "LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)";
+        let windows = normalize(&as_windows(single)).into_owned();
+        assert_eq!(solve_part_one(&windows, is_aaa, is_zzz, None), Ok(6));
+    }
+
+    #[test]
+    fn jumps_land_where_walking_does() {
+        let directions = parse_directions("LR").unwrap();
+        let graph = Graph::new(&create_node_hash(GHOSTS).unwrap()).unwrap();
+        let table = JumpTable::new(&graph, &directions).unwrap();
+        for start in 0..graph.len() {
+            let mut node = start;
+            for k in 0..50 {
+                assert_eq!(table.position(start, k), node);
+                node = graph.step(node, directions[k as usize % 2]);
+            }
+        }
+
+        // 22A reaches 22Z every 3 steps once it gets going, so 10^18 lands on 22B
+        let start = graph.id("22A").unwrap();
+        let far = table.position(start, 1_000_000_000_000_000_000);
+        assert_eq!(graph.label(far), "22B");
+
+        let empty = JumpTable::new(&graph, &[]);
+        assert!(matches!(empty, Err(NetworkError::NoDirections)));
+    }
+
+    #[test]
+    fn exports_the_network() {
+        let input = "LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)";
+        let graph = Graph::new(&create_node_hash(input).unwrap()).unwrap();
+        assert_eq!(
+            graph.to_adjacency(),
+            "AAA BBB BBB\nBBB AAA ZZZ\nZZZ ZZZ ZZZ\n"
+        );
+
+        let path = HashSet::from([("BBB".to_string(), Direction::Right)]);
+        let dot = graph.to_dot(|l| l == "AAA", |l| l == "ZZZ", &path);
+        assert!(dot.starts_with("digraph network {\n"));
+        assert!(dot.contains("\"AAA\" [style=filled, fillcolor=palegreen];"));
+        assert!(dot.contains("\"ZZZ\" [style=filled, fillcolor=salmon];"));
+        assert!(dot.contains("\"BBB\" -> \"ZZZ\" [label=\"R\", color=blue, penwidth=3];"));
+        assert!(dot.contains("\"BBB\" -> \"AAA\" [label=\"L\"];"));
+    }
+
+    #[test]
+    fn analyses_both_kinds_of_walk() {
+        let input = "L\n\nAAA = (BBB, XXX)\nBBB = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)\nXXX = (XXX, XXX)";
+        let graph = Graph::new(&create_node_hash(input).unwrap()).unwrap();
+        let directions = parse_directions("L").unwrap();
+        let [aaa, bbb, xxx, zzz] = ["AAA", "BBB", "XXX", "ZZZ"].map(|l| graph.id(l).unwrap());
+
+        let free = Analysis::new(&graph, &directions, Mode::Free);
+        assert_eq!(free.cycles, vec![vec![aaa, bbb], vec![xxx], vec![zzz]]);
+        assert_eq!(free.reachable, vec![(aaa, vec![zzz])]);
+        assert_eq!(free.dead_ends, vec![xxx]);
+        assert_eq!(
+            free.self_loops,
+            vec![
+                (xxx, Direction::Left),
+                (xxx, Direction::Right),
+                (zzz, Direction::Left),
+                (zzz, Direction::Right),
+            ]
+        );
+
+        // Only ever turning left, AAA and BBB chase each other and never get anywhere
+        let fixed = Analysis::new(&graph, &directions, Mode::Fixed);
+        assert_eq!(fixed.cycles, vec![vec![aaa, bbb], vec![xxx], vec![zzz]]);
+        assert_eq!(fixed.reachable, vec![(aaa, vec![])]);
+        assert_eq!(fixed.dead_ends, vec![aaa, bbb, xxx]);
+        assert_eq!(
+            fixed.self_loops,
+            vec![(xxx, Direction::Left), (zzz, Direction::Left)]
+        );
+    }
+
+    #[test]
+    fn finds_the_ghost_loops() {
+        let graph = Graph::new(&create_node_hash(GHOSTS).unwrap()).unwrap();
+        let directions = parse_directions("LR").unwrap();
+        let fixed = Analysis::new(&graph, &directions, Mode::Fixed);
+        let names: Vec<Vec<&str>> = fixed
+            .cycles
+            .iter()
+            .map(|c| c.iter().map(|id| graph.label(*id)).collect())
+            .collect();
+        assert_eq!(
+            names,
+            vec![vec!["11B", "11Z"], vec!["22B", "22C", "22Z"], vec!["XXX"]]
+        );
+        let targets: Vec<usize> = fixed.reachable.iter().map(|(_, t)| t.len()).collect();
+        assert_eq!(targets, vec![1, 1]);
+    }
+
+    #[test]
+    fn falls_back_when_lcm_does_not_apply() {
+        // Z at steps 3, 8, 13, ... and 4, 11, 18, ...
+        let ghosts = [
+            Ghost {
+                offset: 1,
+                length: 5,
+                run_in_hits: vec![],
+                loop_hits: vec![3],
+            },
+            Ghost {
+                offset: 2,
+                length: 7,
+                run_in_hits: vec![],
+                loop_hits: vec![4],
+            },
+        ];
+        assert_eq!(all_on_z(&ghosts), Ok(Some(18)));
+
+        // One ghost only touches Z once, before it settles into its loop
+        let ghosts = [
+            Ghost {
+                offset: 5,
+                length: 2,
+                run_in_hits: vec![3],
+                loop_hits: vec![],
+            },
+            Ghost {
+                offset: 0,
+                length: 3,
+                run_in_hits: vec![],
+                loop_hits: vec![0],
+            },
+        ];
+        assert_eq!(all_on_z(&ghosts), Ok(Some(3)));
+    }
+
+    #[test]
+    fn needs_a_ghost_to_follow() {
+        assert_eq!(
+            solve_part_two("L\n\nBBB = (BBB, BBB)"),
+            Err(NetworkError::NoStart)
+        );
+        assert_eq!(all_on_z(&[]), Err(NetworkError::NoStart));
+    }
+
+    #[test]
+    fn gives_up_on_answers_too_big_to_find() {
+        // Three loops of about 2^43 steps with nothing in common line up after about 2^129
+        let n = (1 << 43) + 1;
+        let ghosts: Vec<Ghost> = [n, n + 1, n + 2]
+            .into_iter()
+            .map(|length| Ghost {
+                offset: 0,
+                length,
+                run_in_hits: vec![],
+                loop_hits: vec![length],
+            })
+            .collect();
+        assert_eq!(all_on_z(&ghosts), Err(NetworkError::TooManySteps));
+
+        // Forty hits a loop each, on loops with nothing in common, is 40^4 ways to line up
+        let ghosts: Vec<Ghost> = [41, 43, 47, 53]
+            .into_iter()
+            .map(|length| Ghost {
+                offset: 0,
+                length,
+                run_in_hits: vec![],
+                loop_hits: (0..40).collect(),
+            })
+            .collect();
+        assert_eq!(all_on_z(&ghosts), Err(NetworkError::TooManyCombinations));
+    }
+
+    #[test]
+    fn brute_force_agrees_on_a_lopsided_network() {
+        let input = "LRR
+
+AAA = (ABZ, BBB)
+ABZ = (BBB, AAA)
+BBB = (AAA, ABZ)
+CCA = (CCZ, CCA)
+CCZ = (CCA, CCZ)";
+        let directions: Vec<char> = "LRR".chars().collect();
+        let nodes = crate::create_node_hash(input).unwrap();
+        let mut ghosts = vec!["AAA", "CCA"];
+        let mut step = 0;
+        while !ghosts.iter().all(|g| g.ends_with('Z')) {
+            let node_for = |g: &str| &nodes[g];
+            ghosts = ghosts
+                .iter()
+                .map(|g| match directions[step % 3] {
+                    'L' => node_for(g).left,
+                    _ => node_for(g).right,
+                })
+                .collect();
+            step += 1;
+        }
+        assert_eq!(solve_part_two(input), Ok(Some(step as u128)));
+    }
+}
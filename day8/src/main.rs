@@ -1,93 +1,108 @@
-use std::{collections::HashMap, str::FromStr};
+use std::collections::HashSet;
 
-#[derive(Debug)]
-struct Node {
-    left: String,
-    right: String,
-}
-
-#[derive(Debug)]
-struct ParseError;
+use aoc::{
+    alloc::{allocations, Counting},
+    cli::size_and_seed,
+    input::normalize,
+    parse::number,
+};
+use day8::{
+    create_node_hash, generate, ghosts, parse_directions, solve_part_one, solve_part_two, Analysis,
+    Ghost, Graph, JumpTable, Mode, NetworkError, Step,
+};
 
-impl FromStr for Node {
-    type Err = ParseError;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = s.split_whitespace().collect();
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
 
-        let left = parts[2][1..parts[2].len() - 1].to_string();
-        let right = parts[3][0..parts[3].len() - 1].to_string();
-        Ok(Node { left, right })
+fn main() -> Result<(), NetworkError> {
+    let args: Vec<String> = std::env::args().collect();
+    if let Some((size, seed)) = size_and_seed(&args, "--generate")? {
+        let (input, part_one, part_two) = generate::generate(size, seed);
+        println!("{input}");
+        eprintln!("part_one = {part_one}, part_two = {part_two}");
+        return Ok(());
     }
-}
 
-fn create_node_hash(input: &str) -> HashMap<String, Node> {
-    input
-        .lines()
-        .skip(2)
-        .map(|line| {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            let node = line.parse().unwrap();
-            (parts[0].to_string(), node)
-        })
-        .collect()
-}
+    let input = &*normalize(include_str!("./input.txt"));
+    let mut tracer = |step: &Step| {
+        eprintln!(
+            "step={} from={} direction={:?} to={}",
+            step.count, step.from, step.direction, step.to
+        )
+    };
+    let trace: Option<&mut dyn FnMut(&Step)> = match args.iter().any(|a| a == "--trace") {
+        true => Some(&mut tracer),
+        false => None,
+    };
 
-fn traverse_nodes(directions: String, nodes: HashMap<String, Node>, target: &str) -> usize {
-    let mut count = 1;
-    let mut directions_iterator = directions.chars();
-    let mut next_node = nodes.get("AAA");
+    let flag = |name: &str| {
+        args.windows(2)
+            .find(|w| w[0] == name)
+            .map(|w| w[1].as_str())
+    };
+    let (from, to) = (
+        flag("--from").unwrap_or("AAA"),
+        flag("--to").unwrap_or("ZZZ"),
+    );
+    let is_start = |label: &str| label == from;
+    let is_target = |label: &str| label == to;
 
-    loop {
-        match next_node {
-            Some(node) => {
-                let next_dir = directions_iterator.next();
-                match next_dir {
-                    Some('L') => {
-                        println!("Direction is to go Left");
-                        if node.left.as_str() == target {
-                            return count;
-                        } else {
-                            next_node = nodes.get(node.left.as_str());
-                            count += 1;
-                        }
-                    }
-                    Some('R') => {
-                        println!("Direction is to go Right");
-                        if node.right.as_str() == target {
-                            return count;
-                        } else {
-                            next_node = nodes.get(node.right.as_str());
-                            count += 1;
-                        }
-                    }
-                    Some(_) => unreachable!(),
-                    None => {
-                        println!("Out of chars! resetting the directions");
-                        directions_iterator = directions.chars()
-                    }
-                }
-            }
-            None => {
-                println!("Did not find a next node");
-                break;
-            }
+    let part_one = solve_part_one(input, is_start, is_target, trace)?;
+    dbg!(part_one);
+    let part_two = solve_part_two(input)?;
+    dbg!(part_two);
+
+    if args.iter().any(|a| a == "--dot" || a == "--adjacency") {
+        let graph = Graph::new(&create_node_hash(input)?)?;
+        if args.iter().any(|a| a == "--adjacency") {
+            print!("{}", graph.to_adjacency());
+        }
+        if args.iter().any(|a| a == "--dot") {
+            let mut path = HashSet::new();
+            let mut record = |step: &Step| {
+                path.insert((step.from.to_string(), step.direction));
+            };
+            solve_part_one(input, is_start, is_target, Some(&mut record))?;
+            print!("{}", graph.to_dot(is_start, is_target, &path));
         }
     }
-    count
-}
-
-fn solve_part_one(input: &str, target: &str) -> usize {
-    let directions: String = input.lines().take(1).collect();
-    let nodes = create_node_hash(input);
-    traverse_nodes(directions, nodes, target)
-}
-
-fn solve_part_two() -> usize {
-    0
-}
-
-fn main() {
-    let input = include_str!("./input.txt");
-    let part_one = solve_part_one(input, "ZZZ");
-    dbg!(part_one);
+    if args.iter().any(|a| a == "--analyse") {
+        let directions: String = input.lines().take(1).collect();
+        let directions = parse_directions(&directions)?;
+        let graph = Graph::new(&create_node_hash(input)?)?;
+        for mode in [Mode::Free, Mode::Fixed] {
+            print!(
+                "{}",
+                Analysis::new(&graph, &directions, mode).report(&graph)
+            );
+        }
+        let simple = ghosts(&graph, &directions).iter().all(Ghost::is_simple);
+        println!(
+            "LCM shortcut for part two: {}",
+            if simple { "valid" } else { "not valid" }
+        );
+    }
+    if args.iter().any(|a| a == "--allocs") {
+        let (nodes, parsing) = allocations(|| create_node_hash(input));
+        let nodes = nodes?;
+        let (_, interning) = allocations(|| Graph::new(&nodes));
+        println!(
+            "{} nodes: {parsing} allocations parsing, {interning} interning",
+            nodes.len()
+        );
+    }
+    if let Some(query) = args.windows(3).find(|w| w[0] == "--position") {
+        let directions: String = input.lines().take(1).collect();
+        let directions = parse_directions(&directions)?;
+        let graph = Graph::new(&create_node_hash(input)?)?;
+        let start = graph.id(&query[1]).ok_or(NetworkError::NoStart)?;
+        let steps: u64 = number(&query[2])?;
+        let table = JumpTable::new(&graph, &directions)?;
+        println!(
+            "after {steps} steps from {} the walker is at {}",
+            query[1],
+            graph.label(table.position(start, steps))
+        );
+    }
+    Ok(())
 }